@@ -0,0 +1,183 @@
+//! BPF programs attached to an interface: XDP (IFLA_XDP) and the BPF filters
+//! of the clsact/ingress qdisc, resolved to name, tag and type with bpf(2).
+
+use serde::Serialize;
+use smol_str::SmolStr;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BpfProg {
+    pub id: u32,
+    /// Name, tag and type need CAP_SYS_ADMIN; without it only the id is known.
+    pub name: Option<SmolStr>,
+    pub tag: Option<SmolStr>,
+    #[serde(rename = "type")]
+    pub prog_type: Option<SmolStr>,
+}
+
+impl std::fmt::Display for BpfProg {
+    /// `xdp_pass id 42 tag 3b185187f1855c4c`, or `id 42`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(name) = self.name.as_ref().filter(|n| !n.is_empty()) {
+            write!(f, "{} ", name)?;
+        }
+        write!(f, "id {}", self.id)?;
+        if let Some(tag) = &self.tag {
+            write!(f, " tag {}", tag)?;
+        }
+        if let Some(prog_type) = &self.prog_type {
+            write!(f, " ({})", prog_type)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct XdpProg {
+    /// generic, native or offload.
+    pub mode: SmolStr,
+    pub prog: BpfProg,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TcBpfProg {
+    /// ingress or egress.
+    pub direction: SmolStr,
+    pub pref: u16,
+    /// The name given by the loader, usually the object file and section.
+    pub filter_name: Option<SmolStr>,
+    pub direct_action: bool,
+    pub prog: BpfProg,
+}
+
+#[cfg(target_os = "linux")]
+impl BpfProg {
+    /// Look up program `id` with BPF_PROG_GET_FD_BY_ID and
+    /// BPF_OBJ_GET_INFO_BY_FD. Only the id is kept when that is not allowed.
+    pub fn resolve(id: u32) -> Self {
+        let mut prog = BpfProg {
+            id,
+            ..Default::default()
+        };
+        if let Some(info) = prog_info(id) {
+            let name = &info[64..80];
+            let name = &name[..name.iter().position(|&b| b == 0).unwrap_or(name.len())];
+            prog.name = Some(SmolStr::from(String::from_utf8_lossy(name).as_ref()));
+            prog.tag = Some(SmolStr::from(
+                info[8..16]
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect::<String>(),
+            ));
+            prog.prog_type = Some(prog_type_name(u32::from_ne_bytes(
+                info[0..4].try_into().unwrap(),
+            )));
+        }
+        prog
+    }
+}
+
+/// The first 80 bytes of struct bpf_prog_info, up to and including `name`.
+#[cfg(target_os = "linux")]
+fn prog_info(id: u32) -> Option<[u8; 80]> {
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+    const BPF_PROG_GET_FD_BY_ID: libc::c_long = 13;
+    const BPF_OBJ_GET_INFO_BY_FD: libc::c_long = 15;
+
+    // The members of union bpf_attr used by the two commands.
+    #[repr(C)]
+    struct GetFdById {
+        prog_id: u32,
+        next_id: u32,
+        open_flags: u32,
+    }
+    #[repr(C)]
+    struct GetInfoByFd {
+        bpf_fd: u32,
+        info_len: u32,
+        info: u64,
+    }
+
+    let attr = GetFdById {
+        prog_id: id,
+        next_id: 0,
+        open_flags: 0,
+    };
+    let fd = unsafe {
+        libc::syscall(
+            libc::SYS_bpf,
+            BPF_PROG_GET_FD_BY_ID,
+            &attr as *const GetFdById,
+            std::mem::size_of::<GetFdById>(),
+        )
+    };
+    if fd < 0 {
+        return None;
+    }
+    let fd = unsafe { OwnedFd::from_raw_fd(fd as i32) };
+
+    let mut info = [0u8; 80];
+    let attr = GetInfoByFd {
+        bpf_fd: fd.as_raw_fd() as u32,
+        info_len: info.len() as u32,
+        info: info.as_mut_ptr() as u64,
+    };
+    let ret = unsafe {
+        libc::syscall(
+            libc::SYS_bpf,
+            BPF_OBJ_GET_INFO_BY_FD,
+            &attr as *const GetInfoByFd,
+            std::mem::size_of::<GetInfoByFd>(),
+        )
+    };
+    (ret == 0).then_some(info)
+}
+
+/// BPF_PROG_TYPE_* names, as bpftool prints them.
+#[cfg(target_os = "linux")]
+fn prog_type_name(prog_type: u32) -> SmolStr {
+    let name = match prog_type {
+        1 => "socket_filter",
+        2 => "kprobe",
+        3 => "sched_cls",
+        4 => "sched_act",
+        5 => "tracepoint",
+        6 => "xdp",
+        7 => "perf_event",
+        8 => "cgroup_skb",
+        9 => "cgroup_sock",
+        10 => "lwt_in",
+        11 => "lwt_out",
+        12 => "lwt_xmit",
+        13 => "sock_ops",
+        14 => "sk_skb",
+        15 => "cgroup_device",
+        16 => "sk_msg",
+        17 => "raw_tracepoint",
+        18 => "cgroup_sock_addr",
+        19 => "lwt_seg6local",
+        20 => "lirc_mode2",
+        21 => "sk_reuseport",
+        22 => "flow_dissector",
+        26 => "tracing",
+        27 => "struct_ops",
+        28 => "ext",
+        29 => "lsm",
+        30 => "sk_lookup",
+        31 => "syscall",
+        32 => "netfilter",
+        _ => return smol_str::format_smolstr!("type {}", prog_type),
+    };
+    SmolStr::new_static(name)
+}
+
+/// XDP_ATTACHED_* attach mode names.
+#[cfg(target_os = "linux")]
+pub fn xdp_mode_name(attached: u8) -> Option<&'static str> {
+    match attached {
+        1 => Some("native"),
+        2 => Some("generic"),
+        3 => Some("offload"),
+        _ => None,
+    }
+}
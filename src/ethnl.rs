@@ -0,0 +1,461 @@
+//! Raw ethtool netlink requests.
+//!
+//! The `ethtool` crate does not know LINKINFO, and its LINKMODES decoder keeps
+//! only the bits that are set, losing the supported modes. `EthnlMessage`
+//! carries the attributes of any ethtool message as raw bytes, so that these
+//! replies can be decoded here while still going through the crate's
+//! genetlink connection.
+
+use anyhow::{anyhow, Result};
+use futures::stream::StreamExt;
+use netlink_packet_core::{
+    DecodeError, Emitable, NetlinkMessage, NetlinkPayload, ParseableParametrized, NLM_F_ACK,
+    NLM_F_DUMP, NLM_F_REQUEST,
+};
+use netlink_packet_generic::{GenlFamily, GenlHeader, GenlMessage};
+use smol_str::SmolStr;
+
+pub const ETHTOOL_MSG_LINKINFO_GET: u8 = 2;
+pub const ETHTOOL_MSG_LINKMODES_GET: u8 = 4;
+pub const ETHTOOL_MSG_LINKSTATE_GET: u8 = 6;
+
+// Every ethtool message starts with a nested header attribute of type 1.
+const ETHTOOL_A_HEADER: u16 = 1;
+const ETHTOOL_A_HEADER_DEV_NAME: u16 = 2;
+
+const ETHTOOL_A_LINKINFO_PORT: u16 = 2;
+const ETHTOOL_A_LINKINFO_TRANSCEIVER: u16 = 6;
+
+const ETHTOOL_A_LINKMODES_AUTONEG: u16 = 2;
+const ETHTOOL_A_LINKMODES_OURS: u16 = 3;
+const ETHTOOL_A_LINKMODES_PEER: u16 = 4;
+const ETHTOOL_A_LINKMODES_SPEED: u16 = 5;
+const ETHTOOL_A_LINKMODES_DUPLEX: u16 = 6;
+const ETHTOOL_A_LINKMODES_LANES: u16 = 9;
+
+const ETHTOOL_A_LINKSTATE_LINK: u16 = 2;
+const ETHTOOL_A_LINKSTATE_SQI: u16 = 3;
+const ETHTOOL_A_LINKSTATE_SQI_MAX: u16 = 4;
+const ETHTOOL_A_LINKSTATE_EXT_STATE: u16 = 5;
+const ETHTOOL_A_LINKSTATE_EXT_SUBSTATE: u16 = 6;
+const ETHTOOL_A_LINKSTATE_EXT_DOWN_CNT: u16 = 7;
+
+const ETHTOOL_A_BITSET_NOMASK: u16 = 1;
+const ETHTOOL_A_BITSET_BITS: u16 = 3;
+const ETHTOOL_A_BITSET_BITS_BIT: u16 = 1;
+const ETHTOOL_A_BITSET_BIT_NAME: u16 = 2;
+const ETHTOOL_A_BITSET_BIT_VALUE: u16 = 3;
+
+const NLA_F_NESTED: u16 = 0x8000;
+const NLA_TYPE_MASK: u16 = 0x3fff;
+
+#[derive(Debug, Clone)]
+pub struct EthnlMessage {
+    cmd: u8,
+    attrs: Vec<u8>,
+}
+
+impl GenlFamily for EthnlMessage {
+    fn family_name() -> &'static str {
+        "ethtool"
+    }
+
+    fn version(&self) -> u8 {
+        1
+    }
+
+    fn command(&self) -> u8 {
+        self.cmd
+    }
+}
+
+impl Emitable for EthnlMessage {
+    fn buffer_len(&self) -> usize {
+        self.attrs.len()
+    }
+
+    fn emit(&self, buffer: &mut [u8]) {
+        buffer[..self.attrs.len()].copy_from_slice(&self.attrs);
+    }
+}
+
+impl ParseableParametrized<[u8], GenlHeader> for EthnlMessage {
+    fn parse_with_param(buffer: &[u8], header: GenlHeader) -> Result<Self, DecodeError> {
+        Ok(Self {
+            cmd: header.cmd,
+            attrs: buffer.to_vec(),
+        })
+    }
+}
+
+impl EthnlMessage {
+    /// A GET request for `cmd`: `dev` selects one device, `None` dumps them all.
+    pub fn get(cmd: u8, dev: Option<&str>) -> Self {
+        let mut attrs = Vec::new();
+        if let Some(dev) = dev {
+            let mut name = dev.as_bytes().to_vec();
+            name.push(0);
+            let mut header = Vec::new();
+            put_attr(&mut header, ETHTOOL_A_HEADER_DEV_NAME, &name);
+            put_attr(&mut attrs, ETHTOOL_A_HEADER | NLA_F_NESTED, &header);
+        }
+        Self { cmd, attrs }
+    }
+
+    pub fn attrs(&self) -> Attrs<'_> {
+        Attrs(&self.attrs)
+    }
+
+    /// Device name from the reply header.
+    pub fn dev_name(&self) -> Option<SmolStr> {
+        let header = self.attrs().find(|(kind, _)| *kind == ETHTOOL_A_HEADER)?.1;
+        let name = Attrs(header)
+            .find(|(kind, _)| *kind == ETHTOOL_A_HEADER_DEV_NAME)?
+            .1;
+        Some(SmolStr::from(c_str(name)))
+    }
+}
+
+/// Send `request` on the ethtool connection and collect the replies.
+pub async fn request(
+    handle: &mut ethtool::EthtoolHandle,
+    request: EthnlMessage,
+) -> Result<Vec<EthnlMessage>> {
+    let is_dump = request.attrs.is_empty();
+    let mut msg = NetlinkMessage::from(GenlMessage::from_payload(request));
+    // Same flags as the ethtool crate: NLM_F_ACK makes the reply stream end
+    // after the last part of a dump.
+    msg.header.flags = if is_dump {
+        NLM_F_DUMP | NLM_F_REQUEST | NLM_F_ACK
+    } else {
+        NLM_F_REQUEST
+    };
+
+    let mut replies = Vec::new();
+    let mut stream = Box::pin(
+        handle
+            .handle
+            .request(msg)
+            .await
+            .map_err(|e| anyhow!("{}", e))?,
+    );
+    while let Some(reply) = stream.next().await {
+        match reply?.payload {
+            NetlinkPayload::InnerMessage(genl) => replies.push(genl.payload),
+            NetlinkPayload::Error(err) if err.code.is_some() => {
+                return Err(anyhow!("ethtool request failed: {:?}", err.code))
+            }
+            _ => {}
+        }
+    }
+    Ok(replies)
+}
+
+/// Iterator over the `(type, payload)` pairs of a buffer of netlink attributes.
+pub struct Attrs<'a>(&'a [u8]);
+
+impl<'a> Iterator for Attrs<'a> {
+    type Item = (u16, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let buf = self.0;
+        if buf.len() < 4 {
+            return None;
+        }
+        let len = u16::from_ne_bytes([buf[0], buf[1]]) as usize;
+        let kind = u16::from_ne_bytes([buf[2], buf[3]]) & NLA_TYPE_MASK;
+        if len < 4 || len > buf.len() {
+            return None;
+        }
+        self.0 = &buf[((len + 3) & !3).min(buf.len())..];
+        Some((kind, &buf[4..len]))
+    }
+}
+
+fn put_attr(buf: &mut Vec<u8>, kind: u16, value: &[u8]) {
+    buf.extend_from_slice(&((4 + value.len()) as u16).to_ne_bytes());
+    buf.extend_from_slice(&kind.to_ne_bytes());
+    buf.extend_from_slice(value);
+    buf.resize((buf.len() + 3) & !3, 0);
+}
+
+fn c_str(bytes: &[u8]) -> String {
+    let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..len]).into_owned()
+}
+
+fn u32_attr(value: &[u8]) -> Option<u32> {
+    Some(u32::from_ne_bytes(value.get(..4)?.try_into().ok()?))
+}
+
+/// Port and transceiver from a LINKINFO reply.
+#[derive(Debug, Default)]
+pub struct LinkInfoReply {
+    pub port: Option<u8>,
+    pub transceiver: Option<u8>,
+}
+
+pub fn parse_link_info(msg: &EthnlMessage) -> LinkInfoReply {
+    let mut info = LinkInfoReply::default();
+    for (kind, value) in msg.attrs() {
+        match kind {
+            ETHTOOL_A_LINKINFO_PORT => info.port = value.first().copied(),
+            ETHTOOL_A_LINKINFO_TRANSCEIVER => info.transceiver = value.first().copied(),
+            _ => {}
+        }
+    }
+    info
+}
+
+/// Decoded LINKMODES reply. Bit names are the kernel link mode names
+/// (e.g. `1000baseT/Full`).
+#[derive(Debug, Default)]
+pub struct LinkModesReply {
+    pub autoneg: Option<bool>,
+    pub speed: Option<u32>,
+    pub duplex: Option<u8>,
+    pub lanes: Option<u32>,
+    pub supported: Vec<SmolStr>,
+    pub advertised: Vec<SmolStr>,
+    pub partner: Vec<SmolStr>,
+}
+
+pub fn parse_link_modes(msg: &EthnlMessage) -> LinkModesReply {
+    let mut modes = LinkModesReply::default();
+    for (kind, value) in msg.attrs() {
+        match kind {
+            ETHTOOL_A_LINKMODES_AUTONEG => modes.autoneg = value.first().map(|&v| v != 0),
+            ETHTOOL_A_LINKMODES_SPEED => modes.speed = u32_attr(value),
+            ETHTOOL_A_LINKMODES_DUPLEX => modes.duplex = value.first().copied(),
+            ETHTOOL_A_LINKMODES_LANES => modes.lanes = u32_attr(value),
+            ETHTOOL_A_LINKMODES_OURS => {
+                (modes.supported, modes.advertised) = parse_bitset(value);
+            }
+            ETHTOOL_A_LINKMODES_PEER => modes.partner = parse_bitset(value).1,
+            _ => {}
+        }
+    }
+    modes
+}
+
+/// Decoded LINKSTATE reply.
+#[derive(Debug, Default)]
+pub struct LinkStateReply {
+    pub link: Option<bool>,
+    pub sqi: Option<u32>,
+    pub sqi_max: Option<u32>,
+    pub ext_state: Option<u8>,
+    pub ext_substate: Option<u8>,
+    pub down_count: Option<u32>,
+}
+
+pub fn parse_link_state(msg: &EthnlMessage) -> LinkStateReply {
+    let mut state = LinkStateReply::default();
+    for (kind, value) in msg.attrs() {
+        match kind {
+            ETHTOOL_A_LINKSTATE_LINK => state.link = value.first().map(|&v| v != 0),
+            ETHTOOL_A_LINKSTATE_SQI => state.sqi = u32_attr(value),
+            ETHTOOL_A_LINKSTATE_SQI_MAX => state.sqi_max = u32_attr(value),
+            ETHTOOL_A_LINKSTATE_EXT_STATE => state.ext_state = value.first().copied(),
+            ETHTOOL_A_LINKSTATE_EXT_SUBSTATE => state.ext_substate = value.first().copied(),
+            ETHTOOL_A_LINKSTATE_EXT_DOWN_CNT => state.down_count = u32_attr(value),
+            _ => {}
+        }
+    }
+    state
+}
+
+/// Name of an `enum ethtool_link_ext_state` value.
+pub fn ext_state_name(state: u8) -> &'static str {
+    match state {
+        0 => "autoneg",
+        1 => "link training failure",
+        2 => "link logical mismatch",
+        3 => "bad signal integrity",
+        4 => "no cable",
+        5 => "cable issue",
+        6 => "EEPROM issue",
+        7 => "calibration failure",
+        8 => "power budget exceeded",
+        9 => "overheat",
+        10 => "module",
+        _ => "unknown",
+    }
+}
+
+/// Name of an extended substate; its meaning depends on the extended state.
+pub fn ext_substate_name(state: u8, substate: u8) -> &'static str {
+    match (state, substate) {
+        (0, 1) => "no partner detected",
+        (0, 2) => "ack not received",
+        (0, 3) => "next page exchange failed",
+        (0, 4) => "no partner detected during force mode",
+        (0, 5) => "FEC mismatch during override",
+        (0, 6) => "no HCD",
+        (1, 1) => "KR frame lock not acquired",
+        (1, 2) => "KR link inhibit timeout",
+        (1, 3) => "KR link partner did not set receiver ready",
+        (1, 4) => "remote fault",
+        (2, 1) => "PCS did not acquire block lock",
+        (2, 2) => "PCS did not acquire AM lock",
+        (2, 3) => "PCS did not get align status",
+        (2, 4) => "FC FEC is not locked",
+        (2, 5) => "RS FEC is not locked",
+        (3, 1) => "large number of physical errors",
+        (3, 2) => "unsupported rate",
+        (3, 3) => "serdes reference clock lost",
+        (3, 4) => "serdes ALOS",
+        (5, 1) => "unsupported cable",
+        (5, 2) => "cable test failure",
+        (10, 1) => "CMIS not ready",
+        _ => "unknown",
+    }
+}
+
+/// Decode a verbose bitset into the names of the bits in its mask and the
+/// names of the bits set in its value. Without a mask, the listed bits are
+/// the value and the first list is empty.
+fn parse_bitset(buf: &[u8]) -> (Vec<SmolStr>, Vec<SmolStr>) {
+    let mut nomask = false;
+    let mut listed = Vec::new();
+    let mut set = Vec::new();
+
+    for (kind, value) in Attrs(buf) {
+        match kind {
+            ETHTOOL_A_BITSET_NOMASK => nomask = true,
+            ETHTOOL_A_BITSET_BITS => {
+                for (_, bit) in Attrs(value).filter(|(k, _)| *k == ETHTOOL_A_BITSET_BITS_BIT) {
+                    let mut name = None;
+                    let mut is_set = false;
+                    for (kind, value) in Attrs(bit) {
+                        match kind {
+                            ETHTOOL_A_BITSET_BIT_NAME => name = Some(SmolStr::from(c_str(value))),
+                            ETHTOOL_A_BITSET_BIT_VALUE => is_set = true,
+                            _ => {}
+                        }
+                    }
+                    if let Some(name) = name {
+                        if is_set {
+                            set.push(name.clone());
+                        }
+                        listed.push(name);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if nomask {
+        (Vec::new(), listed)
+    } else {
+        (listed, set)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ETHTOOL_A_BITSET_SIZE: u16 = 2;
+    const ETHTOOL_A_BITSET_BIT_INDEX: u16 = 1;
+
+    fn nested(kind: u16, value: &[u8]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        put_attr(&mut buf, kind | NLA_F_NESTED, value);
+        buf
+    }
+
+    /// A verbose bitset of `(index, name, set)` bits.
+    fn bitset(nomask: bool, bits: &[(u32, &str, bool)]) -> Vec<u8> {
+        let mut list = Vec::new();
+        for &(index, name, set) in bits {
+            let mut bit = Vec::new();
+            put_attr(&mut bit, ETHTOOL_A_BITSET_BIT_INDEX, &index.to_ne_bytes());
+            put_attr(
+                &mut bit,
+                ETHTOOL_A_BITSET_BIT_NAME,
+                format!("{}\0", name).as_bytes(),
+            );
+            if set {
+                put_attr(&mut bit, ETHTOOL_A_BITSET_BIT_VALUE, &[]);
+            }
+            list.extend(nested(ETHTOOL_A_BITSET_BITS_BIT, &bit));
+        }
+        let mut buf = Vec::new();
+        if nomask {
+            put_attr(&mut buf, ETHTOOL_A_BITSET_NOMASK, &[]);
+        }
+        put_attr(&mut buf, ETHTOOL_A_BITSET_SIZE, &100u32.to_ne_bytes());
+        buf.extend(nested(ETHTOOL_A_BITSET_BITS, &list));
+        buf
+    }
+
+    #[test]
+    fn bitset_supported_and_advertised() {
+        let buf = bitset(
+            false,
+            &[
+                (3, "100baseT/Full", false),
+                (5, "1000baseT/Full", true),
+                (6, "Autoneg", true),
+                (12, "10000baseT/Full", false),
+            ],
+        );
+        let (supported, advertised) = parse_bitset(&buf);
+        assert_eq!(
+            supported,
+            [
+                "100baseT/Full",
+                "1000baseT/Full",
+                "Autoneg",
+                "10000baseT/Full"
+            ]
+        );
+        assert_eq!(advertised, ["1000baseT/Full", "Autoneg"]);
+    }
+
+    #[test]
+    fn bitset_without_mask() {
+        let buf = bitset(true, &[(5, "1000baseT/Full", true), (6, "Autoneg", true)]);
+        let (mask, value) = parse_bitset(&buf);
+        assert!(mask.is_empty());
+        assert_eq!(value, ["1000baseT/Full", "Autoneg"]);
+    }
+
+    #[test]
+    fn link_modes_reply() {
+        let mut attrs = Vec::new();
+        put_attr(&mut attrs, ETHTOOL_A_LINKMODES_AUTONEG, &[1]);
+        attrs.extend(nested(
+            ETHTOOL_A_LINKMODES_OURS,
+            &bitset(
+                false,
+                &[(3, "100baseT/Full", false), (5, "1000baseT/Full", true)],
+            ),
+        ));
+        attrs.extend(nested(
+            ETHTOOL_A_LINKMODES_PEER,
+            &bitset(true, &[(3, "100baseT/Full", true)]),
+        ));
+        put_attr(
+            &mut attrs,
+            ETHTOOL_A_LINKMODES_SPEED,
+            &1000u32.to_ne_bytes(),
+        );
+        put_attr(&mut attrs, ETHTOOL_A_LINKMODES_DUPLEX, &[1]);
+        put_attr(&mut attrs, ETHTOOL_A_LINKMODES_LANES, &1u32.to_ne_bytes());
+
+        let modes = parse_link_modes(&EthnlMessage {
+            cmd: ETHTOOL_MSG_LINKMODES_GET,
+            attrs,
+        });
+        assert_eq!(modes.autoneg, Some(true));
+        assert_eq!(modes.speed, Some(1000));
+        assert_eq!(modes.duplex, Some(1));
+        assert_eq!(modes.lanes, Some(1));
+        assert_eq!(modes.supported, ["100baseT/Full", "1000baseT/Full"]);
+        assert_eq!(modes.advertised, ["1000baseT/Full"]);
+        assert_eq!(modes.partner, ["100baseT/Full"]);
+    }
+}
@@ -0,0 +1,1117 @@
+//! Named field registry used by `--fields` and `--format`.
+//!
+//! Every field renders to a list of values. Single-valued fields yield at most
+//! one value; multi-valued fields (`altname`, `ipv4`, `ipv6`, `flags`,
+//! `features`, `vlans`, `slaves`, the `*_modes` and `lacp_*_state` lists) yield one value
+//! per item. When rendered, the values of a field are joined with a comma, and
+//! a field with no value at all is rendered as `-`.
+
+use crate::filter::CollectedInterface;
+use crate::output::format_speed;
+use anyhow::{bail, Result};
+
+pub struct Field {
+    pub name: &'static str,
+    pub help: &'static str,
+    /// The data is only collected on demand: by `--verbose`, `--json` or a
+    /// template that refers to the field.
+    details: bool,
+    get: fn(&CollectedInterface) -> Vec<String>,
+}
+
+fn one<T: ToString>(value: Option<T>) -> Vec<String> {
+    value.map(|v| v.to_string()).into_iter().collect()
+}
+
+fn pci(info: &CollectedInterface) -> Option<&crate::pci_utils::PciDeviceInfo> {
+    info.pci_info.as_ref()
+}
+
+fn state(info: &CollectedInterface) -> Option<&crate::ifr::LinkState> {
+    info.link_state.as_ref()
+}
+
+fn modes(info: &CollectedInterface) -> Option<&crate::ifr::LinkModes> {
+    info.link_modes.as_ref()
+}
+
+fn vlan(info: &CollectedInterface) -> Option<&crate::linkinfo::VlanInfo> {
+    info.link_info.as_ref().and_then(|l| l.vlan.as_ref())
+}
+
+fn bond(info: &CollectedInterface) -> Option<&crate::linkinfo::BondInfo> {
+    info.link_info.as_ref().and_then(|l| l.bond.as_ref())
+}
+
+fn bond_slave(info: &CollectedInterface) -> Option<&crate::linkinfo::BondSlaveInfo> {
+    info.slave_info.as_ref().and_then(|s| s.bond.as_ref())
+}
+
+fn bridge(info: &CollectedInterface) -> Option<&crate::linkinfo::BridgeInfo> {
+    info.link_info.as_ref().and_then(|l| l.bridge.as_ref())
+}
+
+fn bridge_port(info: &CollectedInterface) -> Option<&crate::linkinfo::BridgePortInfo> {
+    info.slave_info.as_ref().and_then(|s| s.bridge.as_ref())
+}
+
+fn root_qdisc(info: &CollectedInterface) -> Option<&crate::tc::Qdisc> {
+    info.qdiscs.iter().find(|q| q.parent == "root")
+}
+
+/// The program name, or its id when it cannot be resolved.
+fn prog_name(prog: &crate::bpf::BpfProg) -> String {
+    match prog.name.as_ref().filter(|n| !n.is_empty()) {
+        Some(name) => name.to_string(),
+        None => prog.id.to_string(),
+    }
+}
+
+fn strings(values: Option<&[smol_str::SmolStr]>) -> Vec<String> {
+    values
+        .unwrap_or_default()
+        .iter()
+        .map(|v| v.to_string())
+        .collect()
+}
+
+pub static FIELDS: &[Field] = &[
+    Field {
+        name: "name",
+        help: "interface name",
+        details: false,
+        get: |i| vec![i.name.to_string()],
+    },
+    Field {
+        name: "netns",
+        help: "network namespace",
+        details: false,
+        get: |i| one(i.netns.as_ref()),
+    },
+    Field {
+        name: "kind",
+        help: "interface kind (veth, bond, vlan...; none for physical NICs)",
+        details: false,
+        get: |i| one(i.link_info.as_ref().map(|l| &l.kind)),
+    },
+    Field {
+        name: "kind_details",
+        help: "kind-specific settings as key=value (multi-valued)",
+        details: false,
+        get: |i| {
+            i.link_info
+                .iter()
+                .flat_map(|l| l.details.iter().map(|(k, v)| format!("{}={}", k, v)))
+                .collect()
+        },
+    },
+    Field {
+        name: "peer",
+        help: "other end of a veth pair, as name@{netns} when in another namespace",
+        details: false,
+        get: |i| one(i.link_info.as_ref().and_then(|l| l.peer.as_ref())),
+    },
+    Field {
+        name: "peer_netns",
+        help: "network namespace of the veth peer, when not this one",
+        details: false,
+        get: |i| {
+            let peer = i.link_info.as_ref().and_then(|l| l.peer.as_ref());
+            one(peer.and_then(|p| p.netns.as_ref()))
+        },
+    },
+    Field {
+        name: "vlan_id",
+        help: "VLAN ID of an 802.1Q/802.1ad sub-interface",
+        details: false,
+        get: |i| one(vlan(i).map(|v| v.id)),
+    },
+    Field {
+        name: "vlan_protocol",
+        help: "VLAN protocol (802.1Q or 802.1ad)",
+        details: false,
+        get: |i| one(vlan(i).map(|v| &v.protocol)),
+    },
+    Field {
+        name: "vlan_parent",
+        help: "lower device of a VLAN",
+        details: false,
+        get: |i| one(vlan(i).and_then(|v| v.parent.as_ref())),
+    },
+    Field {
+        name: "vlan_flags",
+        help: "VLAN flags (reorder_hdr, gvrp, mvrp, loose_binding...; multi-valued)",
+        details: false,
+        get: |i| strings(vlan(i).map(|v| v.flags.as_slice())),
+    },
+    Field {
+        name: "vlan_ingress_qos",
+        help: "VLAN ingress PCP:priority map (multi-valued)",
+        details: false,
+        get: |i| strings(vlan(i).map(|v| v.ingress_qos.as_slice())),
+    },
+    Field {
+        name: "vlan_egress_qos",
+        help: "VLAN egress priority:PCP map (multi-valued)",
+        details: false,
+        get: |i| strings(vlan(i).map(|v| v.egress_qos.as_slice())),
+    },
+    Field {
+        name: "vlans",
+        help: "VLAN sub-interfaces of this interface (multi-valued)",
+        details: false,
+        get: |i| strings(Some(&i.vlans)),
+    },
+    Field {
+        name: "master",
+        help: "bond or bridge this interface is enslaved to",
+        details: false,
+        get: |i| one(i.slave_info.as_ref().and_then(|s| s.master.as_ref())),
+    },
+    Field {
+        name: "slaves",
+        help: "interfaces enslaved to this bond or bridge (multi-valued)",
+        details: false,
+        get: |i| strings(Some(&i.slaves)),
+    },
+    Field {
+        name: "bond_mode",
+        help: "bonding mode (active-backup, 802.3ad...)",
+        details: false,
+        get: |i| one(bond(i).map(|b| &b.mode)),
+    },
+    Field {
+        name: "bond_active_slave",
+        help: "currently active slave of a bond",
+        details: false,
+        get: |i| one(bond(i).and_then(|b| b.active_slave.as_ref())),
+    },
+    Field {
+        name: "bond_miimon",
+        help: "bond MII link monitoring interval in ms",
+        details: false,
+        get: |i| one(bond(i).and_then(|b| b.miimon)),
+    },
+    Field {
+        name: "bond_updelay",
+        help: "bond link up delay in ms",
+        details: false,
+        get: |i| one(bond(i).and_then(|b| b.updelay)),
+    },
+    Field {
+        name: "bond_downdelay",
+        help: "bond link down delay in ms",
+        details: false,
+        get: |i| one(bond(i).and_then(|b| b.downdelay)),
+    },
+    Field {
+        name: "bond_xmit_hash_policy",
+        help: "bond transmit hash policy",
+        details: false,
+        get: |i| one(bond(i).and_then(|b| b.xmit_hash_policy.as_ref())),
+    },
+    Field {
+        name: "bond_lacp_rate",
+        help: "802.3ad LACPDU rate (slow or fast)",
+        details: false,
+        get: |i| one(bond(i).and_then(|b| b.lacp_rate.as_ref())),
+    },
+    Field {
+        name: "bond_aggregator",
+        help: "ID of the active 802.3ad aggregator",
+        details: false,
+        get: |i| one(bond(i).and_then(|b| b.aggregator)),
+    },
+    Field {
+        name: "bond_slave_state",
+        help: "state of a bond slave (active or backup)",
+        details: false,
+        get: |i| one(bond_slave(i).map(|b| &b.state)),
+    },
+    Field {
+        name: "bond_slave_mii_status",
+        help: "MII status of a bond slave",
+        details: false,
+        get: |i| one(bond_slave(i).map(|b| &b.mii_status)),
+    },
+    Field {
+        name: "bond_slave_link_failures",
+        help: "link failure count of a bond slave",
+        details: false,
+        get: |i| one(bond_slave(i).map(|b| b.link_failure_count)),
+    },
+    Field {
+        name: "bond_slave_perm_hwaddr",
+        help: "permanent MAC address of a bond slave",
+        details: false,
+        get: |i| one(bond_slave(i).and_then(|b| b.perm_hwaddr.as_ref())),
+    },
+    Field {
+        name: "bond_slave_aggregator",
+        help: "802.3ad aggregator ID of a bond slave",
+        details: false,
+        get: |i| one(bond_slave(i).and_then(|b| b.aggregator)),
+    },
+    Field {
+        name: "lacp_actor_state",
+        help: "802.3ad actor port state of a bond slave (multi-valued)",
+        details: false,
+        get: |i| strings(bond_slave(i).and_then(|b| b.actor_state.as_deref())),
+    },
+    Field {
+        name: "lacp_partner_state",
+        help: "802.3ad partner port state of a bond slave (multi-valued)",
+        details: false,
+        get: |i| strings(bond_slave(i).and_then(|b| b.partner_state.as_deref())),
+    },
+    Field {
+        name: "bridge_id",
+        help: "bridge ID as priority.mac",
+        details: false,
+        get: |i| one(bridge(i).and_then(|b| b.bridge_id.as_ref())),
+    },
+    Field {
+        name: "bridge_root_id",
+        help: "ID of the STP root bridge",
+        details: false,
+        get: |i| one(bridge(i).and_then(|b| b.root_id.as_ref())),
+    },
+    Field {
+        name: "bridge_stp_state",
+        help: "bridge STP (off, kernel or user)",
+        details: false,
+        get: |i| one(bridge(i).map(|b| &b.stp_state)),
+    },
+    Field {
+        name: "bridge_forward_delay",
+        help: "bridge forward delay in seconds",
+        details: false,
+        get: |i| one(bridge(i).and_then(|b| b.forward_delay)),
+    },
+    Field {
+        name: "bridge_ageing_time",
+        help: "bridge FDB ageing time in seconds",
+        details: false,
+        get: |i| one(bridge(i).and_then(|b| b.ageing_time)),
+    },
+    Field {
+        name: "bridge_vlan_filtering",
+        help: "VLAN filtering enabled on the bridge (true/false)",
+        details: false,
+        get: |i| one(bridge(i).map(|b| b.vlan_filtering)),
+    },
+    Field {
+        name: "port_state",
+        help: "bridge port STP state (forwarding, blocking, learning...)",
+        details: false,
+        get: |i| one(bridge_port(i).map(|p| &p.state)),
+    },
+    Field {
+        name: "port_priority",
+        help: "bridge port priority",
+        details: false,
+        get: |i| one(bridge_port(i).and_then(|p| p.priority)),
+    },
+    Field {
+        name: "port_cost",
+        help: "bridge port path cost",
+        details: false,
+        get: |i| one(bridge_port(i).and_then(|p| p.cost)),
+    },
+    Field {
+        name: "port_flags",
+        help: "bridge port flags that are on (learning, flood, hairpin, neigh_suppress...; multi-valued)",
+        details: false,
+        get: |i| {
+            bridge_port(i)
+                .map(|p| p.flags().iter().map(|f| f.to_string()).collect())
+                .unwrap_or_default()
+        },
+    },
+    Field {
+        name: "bridge_vlans",
+        help: "VLANs allowed on a bridge port, as ranges (multi-valued)",
+        details: true,
+        get: |i| i.bridge_vlans.iter().map(|v| v.range_str()).collect(),
+    },
+    Field {
+        name: "bridge_pvid",
+        help: "PVID of a bridge port",
+        details: true,
+        get: |i| one(i.bridge_vlans.iter().find(|v| v.pvid).map(|v| v.vid)),
+    },
+    Field {
+        name: "bridge_untagged",
+        help: "VLANs sent untagged by a bridge port, as ranges (multi-valued)",
+        details: true,
+        get: |i| {
+            i.bridge_vlans
+                .iter()
+                .filter(|v| v.untagged)
+                .map(|v| v.range_str())
+                .collect()
+        },
+    },
+    Field {
+        name: "state",
+        help: "UP, NO-CARRIER or DOWN",
+        details: false,
+        get: |i| {
+            let state = if i.link_detected {
+                "UP"
+            } else if i.is_up {
+                "NO-CARRIER"
+            } else {
+                "DOWN"
+            };
+            vec![state.to_string()]
+        },
+    },
+    Field {
+        name: "up",
+        help: "administratively up (true/false)",
+        details: false,
+        get: |i| vec![i.is_up.to_string()],
+    },
+    Field {
+        name: "link",
+        help: "link detected (true/false)",
+        details: false,
+        get: |i| vec![i.link_detected.to_string()],
+    },
+    Field {
+        name: "mac",
+        help: "hardware address",
+        details: false,
+        get: |i| one(i.mac.as_ref()),
+    },
+    Field {
+        name: "ipv4",
+        help: "IPv4 addresses as addr/prefix (multi-valued)",
+        details: false,
+        get: |i| {
+            i.ipv4.iter().map(|a| a.cidr()).collect()
+        },
+    },
+    Field {
+        name: "ipv4_secondary",
+        help: "secondary IPv4 addresses as addr/prefix (multi-valued)",
+        details: false,
+        get: |i| {
+            i.ipv4
+                .iter()
+                .filter(|a| a.is_secondary())
+                .map(|a| a.cidr())
+                .collect()
+        },
+    },
+    Field {
+        name: "ipv4_labels",
+        help: "legacy labels of the IPv4 addresses, e.g. eth0:1 (multi-valued)",
+        details: false,
+        get: |i| {
+            i.ipv4
+                .iter()
+                .filter_map(|a| a.label.as_ref())
+                .map(|l| l.to_string())
+                .collect()
+        },
+    },
+    Field {
+        name: "ipv6",
+        help: "IPv6 addresses as addr/prefix (multi-valued)",
+        details: false,
+        get: |i| {
+            i.ipv6
+                .iter()
+                .map(|a| format!("{}/{}", a.addr, a.prefix))
+                .collect()
+        },
+    },
+    Field {
+        name: "ipv6_unusable",
+        help: "IPv6 addresses still tentative or that failed DAD (multi-valued)",
+        details: false,
+        get: |i| {
+            i.ipv6
+                .iter()
+                .filter(|a| a.dad_pending_or_failed())
+                .map(|a| format!("{}/{}", a.addr, a.prefix))
+                .collect()
+        },
+    },
+    Field {
+        name: "default_gw",
+        help: "gateways of the default routes through this interface (multi-valued)",
+        details: false,
+        get: |i| {
+            i.routes
+                .iter()
+                .filter(|r| r.is_default())
+                .filter_map(|r| r.gateway.as_ref())
+                .map(|g| g.to_string())
+                .collect()
+        },
+    },
+    Field {
+        name: "routes",
+        help: "destinations of the default, connected and RA/DHCP routes (multi-valued)",
+        details: false,
+        get: |i| i.routes.iter().map(|r| r.destination.to_string()).collect(),
+    },
+    Field {
+        name: "xdp_mode",
+        help: "XDP attach modes: generic, native or offload (multi-valued)",
+        details: false,
+        get: |i| i.xdp.iter().map(|x| x.mode.to_string()).collect(),
+    },
+    Field {
+        name: "xdp_prog",
+        help: "attached XDP programs, by name or id (multi-valued)",
+        details: false,
+        get: |i| i.xdp.iter().map(|x| prog_name(&x.prog)).collect(),
+    },
+    Field {
+        name: "tc_bpf",
+        help: "BPF programs of the clsact/ingress qdisc, as direction:program (multi-valued)",
+        details: true,
+        get: |i| {
+            i.tc_bpf
+                .iter()
+                .map(|f| format!("{}:{}", f.direction, prog_name(&f.prog)))
+                .collect()
+        },
+    },
+    Field {
+        name: "flags",
+        help: "interface flags (multi-valued)",
+        details: false,
+        get: |i| strings(Some(&i.flags)),
+    },
+    Field {
+        name: "driver",
+        help: "driver name",
+        details: false,
+        get: |i| one(i.driver_info.as_ref().map(|d| &d.driver)),
+    },
+    Field {
+        name: "driver_version",
+        help: "driver version",
+        details: false,
+        get: |i| one(i.driver_info.as_ref().map(|d| &d.version)),
+    },
+    Field {
+        name: "fw_version",
+        help: "firmware version",
+        details: false,
+        get: |i| one(i.driver_info.as_ref().and_then(|d| d.fw_version.as_ref())),
+    },
+    Field {
+        name: "erom_version",
+        help: "expansion ROM version",
+        details: false,
+        get: |i| one(i.driver_info.as_ref().and_then(|d| d.erom_version.as_ref())),
+    },
+    Field {
+        name: "fw_mismatch",
+        help: "another listed NIC with the same PCI IDs runs other firmware (true/false)",
+        details: false,
+        get: |i| vec![i.fw_mismatch.to_string()],
+    },
+    Field {
+        name: "bus",
+        help: "bus info reported by the driver",
+        details: false,
+        get: |i| {
+            one(i
+                .driver_info
+                .as_ref()
+                .map(|d| &d.bus_info)
+                .filter(|b| !b.is_empty()))
+        },
+    },
+    Field {
+        name: "pci",
+        help: "PCI address (domain:bus:device.function)",
+        details: false,
+        get: |i| one(pci(i).and_then(|p| p.pci_address())),
+    },
+    Field {
+        name: "vendor",
+        help: "PCI vendor name",
+        details: false,
+        get: |i| one(pci(i).and_then(|p| p.vendor_name.as_ref())),
+    },
+    Field {
+        name: "device",
+        help: "PCI device name",
+        details: false,
+        get: |i| one(pci(i).and_then(|p| p.device_name.as_ref())),
+    },
+    Field {
+        name: "vendor_id",
+        help: "PCI vendor ID",
+        details: false,
+        get: |i| one(pci(i).map(|p| format!("{:04x}", p.vendor_id))),
+    },
+    Field {
+        name: "device_id",
+        help: "PCI device ID",
+        details: false,
+        get: |i| one(pci(i).map(|p| format!("{:04x}", p.device_id))),
+    },
+    Field {
+        name: "subsystem",
+        help: "PCI subsystem vendor:device IDs",
+        details: false,
+        get: |i| {
+            one(
+                pci(i).and_then(|p| match (p.subsystem_vendor, p.subsystem_device) {
+                    (Some(v), Some(d)) => Some(format!("{:04x}:{:04x}", v, d)),
+                    _ => None,
+                }),
+            )
+        },
+    },
+    Field {
+        name: "class",
+        help: "PCI device class",
+        details: false,
+        get: |i| one(pci(i).map(|p| p.format_class())),
+    },
+    Field {
+        name: "revision",
+        help: "PCI revision",
+        details: false,
+        get: |i| {
+            one(pci(i)
+                .and_then(|p| p.revision)
+                .map(|r| format!("{:02x}", r)))
+        },
+    },
+    Field {
+        name: "numa",
+        help: "NUMA node of the PCI device",
+        details: false,
+        get: |i| one(pci(i).and_then(|p| p.numa_node)),
+    },
+    Field {
+        name: "irq",
+        help: "legacy IRQ of the PCI device",
+        details: false,
+        get: |i| one(pci(i).and_then(|p| p.irq)),
+    },
+    Field {
+        name: "sriov_numvfs",
+        help: "VFs enabled on an SR-IOV physical function",
+        details: false,
+        get: |i| one(pci(i).and_then(|p| p.sriov.as_ref()).map(|s| s.numvfs)),
+    },
+    Field {
+        name: "sriov_totalvfs",
+        help: "VFs supported by an SR-IOV physical function",
+        details: false,
+        get: |i| one(pci(i).and_then(|p| p.sriov.as_ref()).map(|s| s.totalvfs)),
+    },
+    Field {
+        name: "vfs",
+        help: "netdevs of the VFs of a physical function, or their PCI address (multi-valued)",
+        details: false,
+        get: |i| {
+            pci(i)
+                .and_then(|p| p.sriov.as_ref())
+                .map(|s| {
+                    s.vfs
+                        .iter()
+                        .map(|vf| vf.netdev.clone().unwrap_or_else(|| vf.pci_address.clone()))
+                        .collect()
+                })
+                .unwrap_or_default()
+        },
+    },
+    Field {
+        name: "pf",
+        help: "physical function of a VF: its netdev, or its PCI address",
+        details: false,
+        get: |i| {
+            one(pci(i)
+                .and_then(|p| p.physfn.as_ref())
+                .map(|pf| pf.netdev.as_ref().unwrap_or(&pf.pci_address)))
+        },
+    },
+    Field {
+        name: "vf_index",
+        help: "index of a VF on its physical function",
+        details: false,
+        get: |i| one(pci(i).and_then(|p| p.physfn.as_ref()).and_then(|pf| pf.vf_index)),
+    },
+    Field {
+        name: "altname",
+        help: "alternative interface names (multi-valued)",
+        details: false,
+        get: |i| strings(Some(&i.altnames)),
+    },
+    Field {
+        name: "mtu",
+        help: "MTU",
+        details: false,
+        get: |i| one(i.mtu),
+    },
+    Field {
+        name: "metric",
+        help: "interface metric",
+        details: false,
+        get: |i| one(i.metric),
+    },
+    Field {
+        name: "media",
+        help: "media description",
+        details: false,
+        get: |i| one(i.media.as_ref()),
+    },
+    Field {
+        name: "speed",
+        help: "link speed (e.g. 10G)",
+        details: false,
+        get: |i| one(i.speed.map(format_speed)),
+    },
+    Field {
+        name: "link_ext_state",
+        help: "extended link-down reason (e.g. no cable)",
+        details: false,
+        get: |i| one(state(i).and_then(|s| s.ext_state.as_ref())),
+    },
+    Field {
+        name: "link_ext_substate",
+        help: "extended link-down detail (e.g. no partner detected)",
+        details: false,
+        get: |i| one(state(i).and_then(|s| s.ext_substate.as_ref())),
+    },
+    Field {
+        name: "link_down_events",
+        help: "link down events counted by the driver",
+        details: false,
+        get: |i| one(state(i).and_then(|s| s.down_count)),
+    },
+    Field {
+        name: "sqi",
+        help: "signal quality index",
+        details: false,
+        get: |i| one(state(i).and_then(|s| s.sqi)),
+    },
+    Field {
+        name: "sqi_max",
+        help: "maximum signal quality index",
+        details: false,
+        get: |i| one(state(i).and_then(|s| s.sqi_max)),
+    },
+    Field {
+        name: "port",
+        help: "port type (TP, FIBRE, DA...)",
+        details: false,
+        get: |i| one(modes(i).and_then(|m| m.port.as_ref())),
+    },
+    Field {
+        name: "transceiver",
+        help: "transceiver (internal/external)",
+        details: false,
+        get: |i| one(modes(i).and_then(|m| m.transceiver.as_ref())),
+    },
+    Field {
+        name: "autoneg",
+        help: "autonegotiation enabled (true/false)",
+        details: false,
+        get: |i| one(modes(i).and_then(|m| m.autoneg)),
+    },
+    Field {
+        name: "lanes",
+        help: "number of lanes",
+        details: false,
+        get: |i| one(modes(i).and_then(|m| m.lanes)),
+    },
+    Field {
+        name: "supported_modes",
+        help: "supported link modes (multi-valued)",
+        details: false,
+        get: |i| strings(modes(i).map(|m| &m.supported[..])),
+    },
+    Field {
+        name: "advertised_modes",
+        help: "advertised link modes (multi-valued)",
+        details: false,
+        get: |i| strings(modes(i).map(|m| &m.advertised[..])),
+    },
+    Field {
+        name: "partner_modes",
+        help: "link modes advertised by the link partner (multi-valued)",
+        details: false,
+        get: |i| strings(modes(i).map(|m| &m.partner[..])),
+    },
+    Field {
+        name: "mode_mismatch",
+        help: "no link mode in common with the partner (true/false)",
+        details: false,
+        get: |i| one(modes(i).map(|m| m.mismatch)),
+    },
+    Field {
+        name: "rx_bytes",
+        help: "received bytes",
+        details: false,
+        get: |i| one(i.stats.as_ref().map(|s| s.rx_bytes)),
+    },
+    Field {
+        name: "rx_packets",
+        help: "received packets",
+        details: false,
+        get: |i| one(i.stats.as_ref().map(|s| s.rx_packets)),
+    },
+    Field {
+        name: "rx_errors",
+        help: "receive errors",
+        details: false,
+        get: |i| one(i.stats.as_ref().map(|s| s.rx_errors)),
+    },
+    Field {
+        name: "rx_dropped",
+        help: "received packets dropped (including missed)",
+        details: false,
+        get: |i| one(i.stats.as_ref().map(|s| s.rx_dropped)),
+    },
+    Field {
+        name: "rx_fifo",
+        help: "receive FIFO errors",
+        details: false,
+        get: |i| one(i.stats.as_ref().map(|s| s.rx_fifo)),
+    },
+    Field {
+        name: "rx_frame",
+        help: "receive frame, length, overrun and CRC errors",
+        details: false,
+        get: |i| one(i.stats.as_ref().map(|s| s.rx_frame)),
+    },
+    Field {
+        name: "rx_compressed",
+        help: "received compressed packets",
+        details: false,
+        get: |i| one(i.stats.as_ref().map(|s| s.rx_compressed)),
+    },
+    Field {
+        name: "rx_multicast",
+        help: "received multicast packets",
+        details: false,
+        get: |i| one(i.stats.as_ref().map(|s| s.rx_multicast)),
+    },
+    Field {
+        name: "rx_missed",
+        help: "packets missed by the host",
+        details: false,
+        get: |i| one(i.stats.as_ref().and_then(|s| s.rx_missed)),
+    },
+    Field {
+        name: "rx_nohandler",
+        help: "packets dropped for lack of a protocol handler",
+        details: false,
+        get: |i| one(i.stats.as_ref().and_then(|s| s.rx_nohandler)),
+    },
+    Field {
+        name: "rx_otherhost_dropped",
+        help: "packets dropped as addressed to another host",
+        details: false,
+        get: |i| one(i.stats.as_ref().and_then(|s| s.rx_otherhost_dropped)),
+    },
+    Field {
+        name: "tx_bytes",
+        help: "transmitted bytes",
+        details: false,
+        get: |i| one(i.stats.as_ref().map(|s| s.tx_bytes)),
+    },
+    Field {
+        name: "tx_packets",
+        help: "transmitted packets",
+        details: false,
+        get: |i| one(i.stats.as_ref().map(|s| s.tx_packets)),
+    },
+    Field {
+        name: "tx_errors",
+        help: "transmit errors",
+        details: false,
+        get: |i| one(i.stats.as_ref().map(|s| s.tx_errors)),
+    },
+    Field {
+        name: "tx_dropped",
+        help: "transmitted packets dropped",
+        details: false,
+        get: |i| one(i.stats.as_ref().map(|s| s.tx_dropped)),
+    },
+    Field {
+        name: "tx_fifo",
+        help: "transmit FIFO errors",
+        details: false,
+        get: |i| one(i.stats.as_ref().map(|s| s.tx_fifo)),
+    },
+    Field {
+        name: "tx_collisions",
+        help: "collisions",
+        details: false,
+        get: |i| one(i.stats.as_ref().map(|s| s.tx_collisions)),
+    },
+    Field {
+        name: "tx_carrier",
+        help: "carrier, aborted, window and heartbeat errors",
+        details: false,
+        get: |i| one(i.stats.as_ref().map(|s| s.tx_carrier)),
+    },
+    Field {
+        name: "tx_compressed",
+        help: "transmitted compressed packets",
+        details: false,
+        get: |i| one(i.stats.as_ref().map(|s| s.tx_compressed)),
+    },
+    Field {
+        name: "rx_ring",
+        help: "RX ring size",
+        details: true,
+        get: |i| one(i.rings.map(|r| r.rx)),
+    },
+    Field {
+        name: "tx_ring",
+        help: "TX ring size",
+        details: true,
+        get: |i| one(i.rings.map(|r| r.tx)),
+    },
+    Field {
+        name: "rx_channels",
+        help: "RX channels",
+        details: true,
+        get: |i| one(i.channels.map(|c| c.rx)),
+    },
+    Field {
+        name: "tx_channels",
+        help: "TX channels",
+        details: true,
+        get: |i| one(i.channels.map(|c| c.tx)),
+    },
+    Field {
+        name: "other_channels",
+        help: "other channels",
+        details: true,
+        get: |i| one(i.channels.map(|c| c.other)),
+    },
+    Field {
+        name: "combined_channels",
+        help: "combined channels",
+        details: true,
+        get: |i| one(i.channels.map(|c| c.combined)),
+    },
+    Field {
+        name: "qdisc",
+        help: "kind of the root qdisc",
+        details: true,
+        get: |i| one(root_qdisc(i).map(|q| &q.kind)),
+    },
+    Field {
+        name: "qdisc_drops",
+        help: "packets dropped by the root qdisc and its children",
+        details: true,
+        get: |i| {
+            one(root_qdisc(i).map(|q| {
+                q.drops as u64 + q.children.iter().map(|c| c.drops as u64).sum::<u64>()
+            }))
+        },
+    },
+    Field {
+        name: "features",
+        help: "active offload features (multi-valued)",
+        details: true,
+        get: |i| i.features.iter().map(|f| f.to_string()).collect(),
+    },
+];
+
+pub fn print_list() {
+    let width = FIELDS.iter().map(|f| f.name.len()).max().unwrap_or(0);
+    for field in FIELDS {
+        println!("{:width$}  {}", field.name, field.help, width = width);
+    }
+}
+
+pub fn lookup(name: &str) -> Result<&'static Field> {
+    match FIELDS.iter().find(|f| f.name == name) {
+        Some(field) => Ok(field),
+        None => {
+            let names: Vec<&str> = FIELDS.iter().map(|f| f.name).collect();
+            bail!(
+                "unknown field '{}' (available fields: {}; see --list-fields)",
+                name,
+                names.join(", ")
+            )
+        }
+    }
+}
+
+enum Segment {
+    Literal(String),
+    Field(&'static Field),
+}
+
+/// A parsed output template: literal text interleaved with `{field}` references.
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+impl Template {
+    /// Parse a `--format` string. `{name}` is replaced by the field value,
+    /// `{{` and `}}` produce literal braces, and the escapes `\t`, `\n` and
+    /// `\\` are expanded so that they can be passed from a shell unquoted.
+    pub fn parse(format: &str) -> Result<Self> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = format.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(ch) => name.push(ch),
+                            None => bail!("unterminated '{{' in format string"),
+                        }
+                    }
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Field(lookup(name.trim())?));
+                }
+                '}' => bail!("unmatched '}}' in format string"),
+                '\\' => match chars.next() {
+                    Some('t') => literal.push('\t'),
+                    Some('n') => literal.push('\n'),
+                    Some('\\') => literal.push('\\'),
+                    Some(other) => {
+                        literal.push('\\');
+                        literal.push(other);
+                    }
+                    None => literal.push('\\'),
+                },
+                _ => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// Build a template from a comma-separated `--fields` list; the selected
+    /// fields are separated by tabs. Empty entries, as left by a trailing
+    /// comma, are skipped.
+    pub fn from_fields(list: &str) -> Result<Self> {
+        let mut segments = Vec::new();
+        for name in list.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            if !segments.is_empty() {
+                segments.push(Segment::Literal("\t".to_string()));
+            }
+            segments.push(Segment::Field(lookup(name)?));
+        }
+        if segments.is_empty() {
+            bail!("no fields given");
+        }
+        Ok(Self { segments })
+    }
+
+    /// Whether the template refers to any of the on-demand fields.
+    pub fn needs_details(&self) -> bool {
+        self.segments
+            .iter()
+            .any(|s| matches!(s, Segment::Field(f) if f.details))
+    }
+
+    pub fn render(&self, info: &CollectedInterface) -> String {
+        let mut out = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => out.push_str(text),
+                Segment::Field(field) => {
+                    let values = (field.get)(info);
+                    if values.is_empty() {
+                        out.push('-');
+                    } else {
+                        out.push_str(&values.join(","));
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The segments of a template, with the fields written back as `{name}`.
+    fn segments(template: &Template) -> Vec<String> {
+        template
+            .segments
+            .iter()
+            .map(|s| match s {
+                Segment::Literal(text) => text.clone(),
+                Segment::Field(field) => format!("{{{}}}", field.name),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parse_fields_and_literals() {
+        let t = Template::parse("{name} is { mtu }").unwrap();
+        assert_eq!(segments(&t), ["{name}", " is ", "{mtu}"]);
+    }
+
+    #[test]
+    fn parse_escapes() {
+        let t = Template::parse(r"{{{name}}}\t\n\\\x\").unwrap();
+        assert_eq!(segments(&t), ["{", "{name}", "}\t\n\\\\x\\"]);
+    }
+
+    #[test]
+    fn parse_unknown_field() {
+        let err = Template::parse("{name} {nope}").err().unwrap();
+        assert!(err.to_string().starts_with("unknown field 'nope'"));
+    }
+
+    #[test]
+    fn parse_unterminated_brace() {
+        let err = Template::parse("{name").err().unwrap();
+        assert_eq!(err.to_string(), "unterminated '{' in format string");
+        let err = Template::parse("name}").err().unwrap();
+        assert_eq!(err.to_string(), "unmatched '}' in format string");
+    }
+
+    #[test]
+    fn from_fields_skips_empty_entries() {
+        let t = Template::from_fields(" name,,mac ,").unwrap();
+        assert_eq!(segments(&t), ["{name}", "\t", "{mac}"]);
+        assert!(Template::from_fields(" , ").is_err());
+        assert!(Template::from_fields("name,nope").is_err());
+    }
+
+    #[test]
+    fn needs_details() {
+        assert!(!Template::from_fields("name,mac").unwrap().needs_details());
+        assert!(Template::from_fields("name,rx_ring")
+            .unwrap()
+            .needs_details());
+        assert!(Template::parse("{qdisc}").unwrap().needs_details());
+    }
+}
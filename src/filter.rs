@@ -1,52 +1,20 @@
+use crate::bpf::{TcBpfProg, XdpProg};
 use crate::ifr;
+use crate::linkinfo::{self, LinkInfo, SlaveInfo};
 #[cfg(target_os = "macos")]
 use crate::macos;
+use crate::neigh::{self, Neighbor};
+use crate::nic_stats::NicStats;
 use crate::pci_utils;
 use crate::proc;
+use crate::route::Route;
+use crate::sriov::VfInfo;
+use crate::tc::Qdisc;
 use anyhow::Result;
 use owo_colors::OwoColorize;
+use serde::Serialize;
 use smol_str::SmolStr;
-
-#[cfg(target_os = "linux")]
-fn get_altname(interface_name: &str) -> Option<SmolStr> {
-    use futures::stream::TryStreamExt;
-    use rtnetlink::new_connection;
-
-    let rt = tokio::runtime::Builder::new_current_thread()
-        .enable_all()
-        .build()
-        .ok()?;
-
-    rt.block_on(async {
-        let (connection, handle, _) = new_connection().ok()?;
-        tokio::spawn(connection);
-
-        let mut links = handle
-            .link()
-            .get()
-            .match_name(interface_name.to_string())
-            .execute();
-        if let Some(link) = links.try_next().await.ok()? {
-            for nla in &link.nlas {
-                if let rtnetlink::packet::link::nlas::Nla::PropList(prop_list) = nla {
-                    for prop in prop_list {
-                        if let rtnetlink::packet::link::nlas::Prop::AltIfName(altname) = prop {
-                            if !altname.is_empty() {
-                                return Some(SmolStr::from(altname.as_str()));
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        None
-    })
-}
-
-#[cfg(not(target_os = "linux"))]
-fn get_altname(_interface_name: &str) -> Option<SmolStr> {
-    None
-}
+use std::collections::{HashMap, HashSet};
 
 pub struct Matcher {
     pub keywords: Vec<SmolStr>,
@@ -55,15 +23,96 @@ pub struct Matcher {
     pub running: bool,
     pub ignore_case: bool,
     pub all: bool,
+    pub kinds: Vec<SmolStr>,
+}
+
+/// Outcome of `Matcher::prefilter`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prefilter {
+    Reject,
+    Accept,
+    /// Only a keyword match against driver, PCI or media data can decide.
+    Undecided,
 }
 
 impl Matcher {
+    fn kind_matches(&self, link_info: Option<&LinkInfo>) -> bool {
+        let kind = link_info.map_or(linkinfo::KIND_DEVICE, |i| i.kind.as_str());
+        self.kinds.is_empty() || self.kinds.iter().any(|k| k.as_str() == kind)
+    }
+
+    /// Apply the filters that only need the link snapshot, so that rejected
+    /// interfaces never cost an ethtool, sysfs or PCI lookup.
+    pub fn prefilter(&self, link: &proc::Link) -> Prefilter {
+        let is_up = link.flags & ifr::IFF_UP != 0;
+        // The ethtool link state checked by `matches` is never up without
+        // carrier on an interface that is up, so this can only reject.
+        let carrier = is_up && link.carrier.unwrap_or(link.flags & ifr::IFF_RUNNING != 0);
+
+        if self.running && !carrier {
+            return Prefilter::Reject;
+        }
+        if !self.kind_matches(link.link_info.as_ref()) {
+            return Prefilter::Reject;
+        }
+        if self.ipv4 && link.ipv4.is_empty() {
+            return Prefilter::Reject;
+        }
+        if self.ipv6 && link.ipv6.is_empty() {
+            return Prefilter::Reject;
+        }
+
+        let explicit_name_match = self
+            .keywords
+            .iter()
+            .any(|k| k.as_str() == link.name.as_str() || link.altnames.iter().any(|a| a == k));
+        if !self.all && !is_up && !explicit_name_match {
+            return Prefilter::Reject;
+        }
+
+        if self.keywords.is_empty() {
+            return Prefilter::Accept;
+        }
+
+        let flags = ifr::flag_names(link.flags);
+        let mut targets = vec![link.name.as_str()];
+        targets.extend(flags.iter().map(|f| f.as_str()));
+        targets.extend(link.altnames.iter().map(|a| a.as_str()));
+        targets.extend(link.link_info.as_ref().map(|i| i.kind.as_str()));
+        targets.extend(link.mac.as_deref());
+        targets.extend(link.ipv4.iter().map(|a| a.addr.as_str()));
+        targets.extend(link.ipv6.iter().map(|a| a.addr.as_str()));
+
+        if self
+            .keywords
+            .iter()
+            .any(|k| self.contains_any(k.as_str(), &targets))
+        {
+            return Prefilter::Accept;
+        }
+
+        Prefilter::Undecided
+    }
+
+    fn contains_any(&self, keyword: &str, targets: &[&str]) -> bool {
+        if self.ignore_case {
+            let k = keyword.to_lowercase();
+            targets.iter().any(|t| t.to_lowercase().contains(&k))
+        } else {
+            targets.iter().any(|t| t.contains(keyword))
+        }
+    }
+
     pub fn matches(&self, info: &CollectedInterface) -> bool {
         // 1. Check -r (running)
         if self.running && !info.link_detected {
             return false;
         }
 
+        if !self.kind_matches(info.link_info.as_ref()) {
+            return false;
+        }
+
         // 2. Check -4 (ipv4)
         if self.ipv4 && info.ipv4.is_empty() {
             return false;
@@ -78,7 +127,7 @@ impl Matcher {
         let explicit_name_match = if !self.keywords.is_empty() {
             self.keywords
                 .iter()
-                .any(|k| k.as_str() == info.name.as_str())
+                .any(|k| k.as_str() == info.name.as_str() || info.altnames.iter().any(|a| a == k))
         } else {
             false
         };
@@ -91,11 +140,11 @@ impl Matcher {
         if !self.keywords.is_empty() {
             let mut any_keyword_matched = false;
 
-            let targets = vec![
-                info.name.as_str(),
-                info.flags_str.as_str(),
-                info.media.as_str(),
-            ];
+            let mut targets = vec![info.name.as_str()];
+            targets.extend(info.flags.iter().map(|f| f.as_str()));
+            targets.extend(info.media.as_deref());
+            targets.extend(info.altnames.iter().map(|a| a.as_str()));
+            targets.extend(info.link_info.as_ref().map(|i| i.kind.as_str()));
 
             for keyword in &self.keywords {
                 if self.check_match(keyword.as_str(), &targets, info) {
@@ -139,28 +188,33 @@ impl Matcher {
             }
         }
 
-        for (ip, _, _) in &info.ipv4 {
-            if check(ip.as_str()) {
+        for inet in &info.ipv4 {
+            if check(inet.addr.as_str()) {
                 return true;
             }
         }
 
-        for (ip, _, _) in &info.ipv6 {
-            if check(ip.as_str()) {
+        for inet6 in &info.ipv6 {
+            if check(inet6.addr.as_str()) {
                 return true;
             }
         }
 
-        if let Some((drv, ver, bus)) = &info.driver_info {
-            if check(drv.as_str()) {
+        if let Some(drv) = &info.driver_info {
+            if check(drv.driver.as_str()) {
                 return true;
             }
-            if check(ver.as_str()) {
+            if check(drv.version.as_str()) {
                 return true;
             }
-            if check(bus.as_str()) {
+            if check(drv.bus_info.as_str()) {
                 return true;
             }
+            for fw in [&drv.fw_version, &drv.erom_version].into_iter().flatten() {
+                if check(fw.as_str()) {
+                    return true;
+                }
+            }
         }
 
         if let Some(pci) = &info.pci_info {
@@ -185,51 +239,112 @@ impl Matcher {
     }
 }
 
+/// Set `fw_mismatch` on the interfaces whose PCI vendor:device ID is shared
+/// by another interface in `interfaces` running a different firmware version.
+pub fn flag_firmware_mismatch(interfaces: &mut [CollectedInterface]) {
+    let key = |info: &CollectedInterface| {
+        let pci = info.pci_info.as_ref()?;
+        let fw = info.driver_info.as_ref()?.fw_version.clone()?;
+        Some(((pci.vendor_id, pci.device_id), fw))
+    };
+
+    let mut versions: HashMap<(u16, u16), HashSet<SmolStr>> = HashMap::new();
+    for info in interfaces.iter() {
+        if let Some((ids, fw)) = key(info) {
+            versions.entry(ids).or_default().insert(fw);
+        }
+    }
+
+    for info in interfaces.iter_mut() {
+        if let Some((ids, _)) = key(info) {
+            info.fw_mismatch = versions.get(&ids).is_some_and(|v| v.len() > 1);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DriverInfo {
+    pub driver: SmolStr,
+    pub version: SmolStr,
+    pub bus_info: SmolStr,
+    pub fw_version: Option<SmolStr>,
+    pub erom_version: Option<SmolStr>,
+}
+
+#[derive(Debug, Serialize)]
 pub struct CollectedInterface {
     pub name: SmolStr,
     pub netns: Option<SmolStr>,
+    pub link_info: Option<LinkInfo>,
+    pub vlans: Vec<SmolStr>, // VLAN sub-interfaces on top of this one
+    pub slave_info: Option<SlaveInfo>,
+    pub slaves: Vec<SmolStr>, // links enslaved to this bond or bridge
+    pub bridge_vlans: Vec<linkinfo::BridgeVlan>, // only with details
+    pub routes: Vec<Route>,
+    pub neighbors: Option<Vec<Neighbor>>, // only with --neigh
+    pub qdiscs: Vec<Qdisc>,               // only with details
+    pub xdp: Vec<XdpProg>,
+    pub tc_bpf: Vec<TcBpfProg>,
+    pub vfs: Vec<VfInfo>, // SR-IOV VFs of a physical function
     pub is_up: bool,
     pub link_detected: bool,
     pub mac: Option<SmolStr>,
-    pub ipv4: Vec<(SmolStr, SmolStr, i32)>, // addr, mask, prefix
-    pub ipv6: Vec<(SmolStr, u32, SmolStr)>, // addr, prefix, scope
-    pub flags_str: SmolStr,
-    pub driver_info: Option<(SmolStr, SmolStr, SmolStr)>, // driver, version, bus_info
+    pub ipv4: Vec<ifr::Inet4Addr>,
+    pub ipv6: Vec<proc::Inet6Addr>,
+    pub flags: Vec<SmolStr>,
+    pub driver_info: Option<DriverInfo>,
+    pub fw_mismatch: bool, // another listed NIC with the same PCI IDs runs other firmware
     pub pci_info: Option<pci_utils::PciDeviceInfo>,
-    pub altname: Option<SmolStr>,
-    pub mtu: i32,
-    pub metric: i32,
-    pub media: SmolStr,
+    pub altnames: Vec<SmolStr>,
+    pub mtu: Option<u32>,
+    pub metric: Option<i32>,
+    pub media: Option<SmolStr>,
+    pub speed: Option<u32>, // Mb/s
     pub stats: Option<proc::Stats>,
-    #[cfg(target_os = "linux")]
-    pub rings: Option<(u32, u32)>, // rx, tx
-    #[cfg(target_os = "linux")]
-    pub channels: Option<(u32, u32, u32, u32)>, // rx, tx, other, combined
-    #[cfg(target_os = "linux")]
+    pub rings: Option<ifr::Rings>,
+    pub channels: Option<ifr::Channels>,
+    pub link_state: Option<ifr::LinkState>,
+    pub link_modes: Option<ifr::LinkModes>,
     pub features: Vec<SmolStr>, // active offload features
+    pub nic_stats: Option<NicStats>,
 }
 
 impl CollectedInterface {
     pub fn gather(
-        nic: &proc::LinuxNic,
-        #[cfg(not(target_os = "macos"))] pci_devices: &std::collections::HashMap<
-            SmolStr,
-            pci_utils::PciDeviceInfo,
-        >,
+        link: &proc::Link,
+        netns: Option<&SmolStr>,
+        ethtool: Option<&ifr::EthtoolInfo>,
+        nic_stats_filter: Option<&str>,
+        #[cfg(not(target_os = "macos"))] pci_devices: &HashMap<SmolStr, pci_utils::PciDeviceInfo>,
     ) -> Result<Self> {
-        let name = &nic.name;
-        let iif = ifr::Interface::new(name)?;
+        let name = &link.name;
+        let iif = ifr::Interface::new(name, link.flags)?;
 
         let is_up = iif.is_up();
-        let link_detected = iif.ethtool_link().unwrap_or(false);
-        let mac = iif.mac().ok().filter(|m| !m.is_empty());
+        // Carrier from the ethtool link state, falling back on IFF_RUNNING for
+        // drivers that do not report it.
+        let link_state = ethtool.and_then(|e| e.link_state.clone());
+        let link_detected = link_state
+            .as_ref()
+            .and_then(|s| s.link)
+            .unwrap_or_else(|| iif.is_running());
+        let mac = link.mac.clone().filter(|m| !m.is_empty());
 
-        let ipv4 = iif.inet_addrs();
-        let ipv6 = proc::get_inet6_addr(name).unwrap_or_default();
+        let ipv4 = link.ipv4.clone();
+        let ipv6 = link.ipv6.clone();
 
-        let flags_str = iif.flags_str();
+        let flags = iif.flag_names();
 
         let drv_info_raw = iif.ethtool_drvinfo().ok();
+
+        let nic_stats = match (nic_stats_filter, &drv_info_raw) {
+            (Some(filter), Some(info)) => iif
+                .ethtool_stats(info.n_stats)
+                .ok()
+                .map(|raw| NicStats::new(raw, filter)),
+            _ => None,
+        };
+
         let driver_info = if let Some(info) = drv_info_raw {
             let drv_str =
                 unsafe { std::ffi::CStr::from_ptr(info.driver.as_ptr()) }.to_string_lossy();
@@ -237,16 +352,28 @@ impl CollectedInterface {
                 unsafe { std::ffi::CStr::from_ptr(info.version.as_ptr()) }.to_string_lossy();
             let bus_str =
                 unsafe { std::ffi::CStr::from_ptr(info.bus_info.as_ptr()) }.to_string_lossy();
-            Some((
-                SmolStr::from(drv_str),
-                SmolStr::from(ver_str),
-                SmolStr::from(bus_str),
-            ))
+            let fw_str =
+                unsafe { std::ffi::CStr::from_ptr(info.fw_version.as_ptr()) }.to_string_lossy();
+            let erom_str =
+                unsafe { std::ffi::CStr::from_ptr(info.erom_version.as_ptr()) }.to_string_lossy();
+            // Drivers without firmware leave these empty or report "N/A".
+            let version = |v: &str| Some(SmolStr::from(v)).filter(|v| !v.is_empty() && v != "N/A");
+            Some(DriverInfo {
+                driver: SmolStr::from(drv_str),
+                version: SmolStr::from(ver_str),
+                bus_info: SmolStr::from(bus_str),
+                fw_version: version(&fw_str),
+                erom_version: version(&erom_str),
+            })
         } else {
             #[cfg(target_os = "macos")]
             {
-                macos::get_driver_info(name).map(|(drv, ver, bus)| {
-                    (SmolStr::from(drv), SmolStr::from(ver), SmolStr::from(bus))
+                macos::get_driver_info(name).map(|(drv, ver, bus)| DriverInfo {
+                    driver: SmolStr::from(drv),
+                    version: SmolStr::from(ver),
+                    bus_info: SmolStr::from(bus),
+                    fw_version: None,
+                    erom_version: None,
                 })
             }
             #[cfg(not(target_os = "macos"))]
@@ -257,7 +384,7 @@ impl CollectedInterface {
 
         let bus_str_owned = driver_info
             .as_ref()
-            .map(|(_, _, b)| b.as_str())
+            .map(|d| d.bus_info.as_str())
             .unwrap_or_default();
 
         #[cfg(not(target_os = "macos"))]
@@ -267,55 +394,76 @@ impl CollectedInterface {
         #[cfg(target_os = "macos")]
         let pci_info = macos::get_pci_info_from_ioreg(name);
 
-        let mtu = iif.mtu().unwrap_or(0);
-        let metric = iif.metric().unwrap_or(0);
-
-        let media = iif
-            .media()
-            .unwrap_or_else(|_| SmolStr::new_static("unknown"));
+        let mtu = link
+            .mtu
+            .or_else(|| iif.mtu().ok().and_then(|mtu| u32::try_from(mtu).ok()));
+        let metric = iif.metric().ok();
 
-        let stats = proc::get_stats(name).ok();
+        let media = ethtool
+            .and_then(|e| e.media.clone())
+            .filter(|m| m != "unknown");
+        let speed = ethtool.and_then(|e| e.speed);
 
-        #[cfg(target_os = "linux")]
-        let rings = iif.ethtool_rings().ok();
-        #[cfg(target_os = "linux")]
-        let channels = iif.ethtool_channels().ok();
-        #[cfg(target_os = "linux")]
-        let features = iif.ethtool_features().unwrap_or_default();
+        let stats = match &link.stats {
+            Some(stats) => Some(stats.clone()),
+            None => proc::get_stats(name).ok(),
+        };
 
-        let altname = get_altname(name);
+        let link_modes = ethtool.and_then(|e| e.link_modes.clone());
+        let rings = ethtool.and_then(|e| e.rings);
+        let channels = ethtool.and_then(|e| e.channels);
+        let features = ethtool.and_then(|e| e.features.clone()).unwrap_or_default();
 
         Ok(Self {
             name: name.clone(),
-            netns: nic.netns.clone(),
+            netns: netns.cloned(),
+            link_info: link.link_info.clone(),
+            vlans: link.vlans.clone(),
+            slave_info: link.slave_info.clone(),
+            slaves: link.slaves.clone(),
+            bridge_vlans: link.bridge_vlans.clone(),
+            routes: link.routes.clone(),
+            neighbors: link.neighbors.clone(),
+            qdiscs: link.qdiscs.clone(),
+            xdp: link.xdp.clone(),
+            tc_bpf: link.tc_bpf.clone(),
+            vfs: link.vfs.clone(),
             is_up,
             link_detected,
             mac,
             ipv4,
             ipv6,
-            flags_str,
+            flags,
             driver_info,
+            fw_mismatch: false,
             pci_info,
-            altname,
+            altnames: link.altnames.clone(),
             mtu,
             metric,
             media,
+            speed,
             stats,
-            #[cfg(target_os = "linux")]
             rings,
-            #[cfg(target_os = "linux")]
             channels,
-            #[cfg(target_os = "linux")]
+            link_state,
+            link_modes,
             features,
+            nic_stats,
         })
     }
 
     pub fn print(&self, verbose: bool) {
         if self.link_detected {
             print!("{} ", self.name.bold().bright_blue());
-            print!("{}", "[link-up]".bright_black());
         } else {
             print!("{} ", self.name.blue());
+        }
+        if let Some(info) = &self.link_info {
+            print!("{} ", format!("({})", info.kind).cyan());
+        }
+        if self.link_detected {
+            print!("{}", "[link-up]".bright_black());
+        } else {
             print!("{}", "[link-down]".bright_black());
         }
 
@@ -327,31 +475,216 @@ impl CollectedInterface {
 
         let indent = "  ";
 
+        if let Some(info) = self.link_info.as_ref().filter(|i| !i.details.is_empty()) {
+            println!("{}Details:  {}", indent, info.details_str());
+        }
+
+        if let Some(peer) = self.link_info.as_ref().and_then(|i| i.peer.as_ref()) {
+            println!("{}Peer:     {}", indent, peer.to_string().blue());
+        }
+
+        if let Some(vlan) = self.link_info.as_ref().and_then(|i| i.vlan.as_ref()) {
+            println!("{}VLAN:     {}", indent, vlan.summary());
+            if let Some(parent) = &vlan.parent {
+                println!("{}Parent:   {}", indent, parent.blue());
+            }
+            if !vlan.ingress_qos.is_empty() || !vlan.egress_qos.is_empty() {
+                println!(
+                    "{}QoS map:  ingress {} egress {}",
+                    indent,
+                    qos_str(&vlan.ingress_qos),
+                    qos_str(&vlan.egress_qos)
+                );
+            }
+        }
+
+        if !self.vlans.is_empty() {
+            println!("{}VLANs:    {}", indent, self.vlans.join(" ").blue());
+        }
+
+        if let Some(bond) = self.link_info.as_ref().and_then(|i| i.bond.as_ref()) {
+            println!("{}Bond:     {}", indent, bond.summary());
+            if let Some(active) = &bond.active_slave {
+                println!("{}Active:   {}", indent, active.blue());
+            }
+        }
+
+        if let Some(bridge) = self.link_info.as_ref().and_then(|i| i.bridge.as_ref()) {
+            println!("{}Bridge:   {}", indent, bridge.summary());
+        }
+
+        if !self.slaves.is_empty() {
+            let label = match &self.link_info {
+                Some(info) if info.kind == "bridge" => "Ports:    ",
+                _ => "Slaves:   ",
+            };
+            println!("{}{}{}", indent, label, self.slaves.join(" ").blue());
+        }
+
+        if let Some(slave) = &self.slave_info {
+            if let Some(master) = &slave.master {
+                println!("{}Master:   {} ({})", indent, master.blue(), slave.kind);
+            }
+            if let Some(port) = &slave.bridge {
+                println!("{}Br port:  {}", indent, port.summary());
+            }
+            if let Some(bond) = &slave.bond {
+                let mii = if bond.mii_status == "up" {
+                    format!("mii {}", bond.mii_status)
+                } else {
+                    format!("mii {}", bond.mii_status).red().to_string()
+                };
+                let mut line = format!("{} {}", bond.state, mii);
+                if bond.link_failure_count > 0 {
+                    let failures = format!("{} link failures", bond.link_failure_count);
+                    line.push_str(&format!(", {}", failures.red()));
+                }
+                if let Some(perm) = &bond.perm_hwaddr {
+                    line.push_str(&format!(", perm {}", perm));
+                }
+                if let Some(aggregator) = bond.aggregator {
+                    line.push_str(&format!(", aggregator {}", aggregator));
+                }
+                println!("{}Bond leg: {}", indent, line);
+
+                if let Some(actor) = &bond.actor_state {
+                    let actor = actor.join(",");
+                    let actor = if bond.lacp_ok() {
+                        actor
+                    } else {
+                        actor.red().to_string()
+                    };
+                    let partner = bond
+                        .partner_state
+                        .as_ref()
+                        .map(|p| p.join(","))
+                        .unwrap_or_else(|| "-".to_string());
+                    println!("{}LACP:     actor {} partner {}", indent, actor, partner);
+                }
+            }
+        }
+
         if let Some(mac) = &self.mac {
             println!("{}MAC:      {}", indent, mac.blue());
         }
 
-        for (addr, _mask, prefix) in &self.ipv4 {
-            println!("{}IPv4:     {}/{}", indent, addr.blue(), prefix);
+        for inet in &self.ipv4 {
+            let prefix = inet.prefix.map(|p| format!("/{}", p)).unwrap_or_default();
+            if inet.is_secondary() {
+                print!("{}IPv4 2nd: {}{}", indent, inet.addr.cyan(), prefix);
+            } else {
+                print!("{}IPv4:     {}{}", indent, inet.addr.blue(), prefix);
+            }
+            let mut extra = Vec::new();
+            if let Some(peer) = &inet.peer {
+                extra.push(format!("peer {}", peer));
+            }
+            if let Some(brd) = &inet.broadcast {
+                extra.push(format!("brd {}", brd));
+            }
+            if let Some(label) = &inet.label {
+                extra.push(format!("label {}", label));
+            }
+            if let Some(proto) = &inet.proto {
+                extra.push(format!("proto {}", proto));
+            }
+            // permanent is the common case; lifetimes tell the others apart.
+            extra.extend(
+                inet.flags
+                    .iter()
+                    .filter(|f| *f != "secondary" && *f != "permanent")
+                    .map(|f| f.to_string()),
+            );
+            let lifetimes = lifetimes_str(inet.valid_lft, inet.preferred_lft);
+            let details = format!("{}{}", extra.join(" "), lifetimes);
+            let details = details.trim_start();
+            if !details.is_empty() {
+                print!(" {}", details.dimmed());
+            }
+            println!();
+        }
+
+        for inet6 in &self.ipv6 {
+            print!("{}IPv6:     {}/{}", indent, inet6.addr.blue(), inet6.prefix);
+            print!(" {}", format!("scope {}", inet6.scope).dimmed());
+            // permanent is the common case; lifetimes tell the others apart.
+            for flag in inet6.flags.iter().filter(|f| *f != "permanent") {
+                if flag == "tentative" || flag == "dadfailed" {
+                    print!(" {}", flag.red().bold());
+                } else {
+                    print!(" {}", flag.dimmed());
+                }
+            }
+            let lifetimes = lifetimes_str(inet6.valid_lft, inet6.preferred_lft);
+            if !lifetimes.is_empty() {
+                print!("{}", lifetimes.dimmed());
+            }
+            println!();
         }
 
-        for (addr, plen, _scope) in &self.ipv6 {
-            println!("{}IPv6:     {}/{}", indent, addr.blue(), plen);
+        // Default routes first, then the connected and learned prefixes.
+        let routes = self.routes.iter().filter(|r| r.is_default());
+        for route in routes.chain(self.routes.iter().filter(|r| !r.is_default())) {
+            let destination = if route.is_default() {
+                route.destination.bold().to_string()
+            } else {
+                route.destination.to_string()
+            };
+            println!(
+                "{}Route:    {} {}",
+                indent,
+                destination,
+                route.details_str().dimmed()
+            );
         }
 
-        if !self.flags_str.is_empty() {
-            println!("{}Flags:    {}", indent, self.flags_str.dimmed());
+        if !self.flags.is_empty() {
+            println!("{}Flags:    {}", indent, self.flags.join(" ").dimmed());
         }
 
-        if let Some((drv, ver, bus)) = &self.driver_info {
-            println!("{}Driver:   {} (v: {})", indent, drv.blue().bold(), ver);
-            if !bus.is_empty() {
-                println!("{}Bus:      {}", indent, bus);
+        for xdp in &self.xdp {
+            println!("{}XDP:      {} {}", indent, xdp.mode.bold(), xdp.prog);
+        }
+        for filter in &self.tc_bpf {
+            let mut attrs = vec![format!("pref {}", filter.pref)];
+            attrs.extend(filter.filter_name.iter().map(|n| n.to_string()));
+            if filter.direct_action {
+                attrs.push("direct-action".to_string());
             }
+            println!(
+                "{}TC BPF:   {} {} {}",
+                indent,
+                filter.direction.bold(),
+                filter.prog,
+                attrs.join(" ").dimmed()
+            );
         }
 
-        if let Some(altname) = &self.altname {
-            println!("{}Altname:  {}", indent, altname.blue());
+        if let Some(drv) = &self.driver_info {
+            let mut versions = format!("v: {}", drv.version);
+            if let Some(fw) = &drv.fw_version {
+                versions.push_str(&format!(", fw: {}", fw));
+            }
+            if let Some(erom) = &drv.erom_version {
+                versions.push_str(&format!(", erom: {}", erom));
+            }
+            print!(
+                "{}Driver:   {} ({})",
+                indent,
+                drv.driver.blue().bold(),
+                versions
+            );
+            if self.fw_mismatch {
+                print!(" {}", "[firmware differs from same-model NICs]".red());
+            }
+            println!();
+            if !drv.bus_info.is_empty() {
+                println!("{}Bus:      {}", indent, drv.bus_info);
+            }
+        }
+
+        if !self.altnames.is_empty() {
+            println!("{}Altnames: {}", indent, self.altnames.join(" ").blue());
         }
 
         if let Some(pci_info) = &self.pci_info {
@@ -373,35 +706,127 @@ impl CollectedInterface {
                 );
             }
 
+            if let Some(pf) = &pci_info.physfn {
+                let name = pf.netdev.as_deref().unwrap_or(&pf.pci_address);
+                let mut line = format!("{} ({})", name.blue(), pf.pci_address);
+                if let Some(index) = pf.vf_index {
+                    line.push_str(&format!(" VF {}", index));
+                }
+                println!("{}PF:       {}", indent, line);
+            }
+
+            if let Some(sriov) = &pci_info.sriov {
+                println!(
+                    "{}SR-IOV:   {}/{} VFs enabled",
+                    indent, sriov.numvfs, sriov.totalvfs
+                );
+                print_vfs(&self.vfs, sriov, indent);
+            }
+
             if verbose {
                 // Verbose PCI info
             }
         }
 
-        println!("{}MTU:      {} (Metric: {})", indent, self.mtu, self.metric);
+        if let Some(mtu) = self.mtu {
+            match self.metric {
+                Some(metric) => println!("{}MTU:      {} (Metric: {})", indent, mtu, metric),
+                None => println!("{}MTU:      {}", indent, mtu),
+            }
+        }
+
+        if let Some(media) = &self.media {
+            println!("{}Media:    {}", indent, media.dimmed());
+        }
 
-        if self.media != "unknown" {
-            println!("{}Media:    {}", indent, self.media.dimmed());
+        if let Some(state) = &self.link_state {
+            let mut parts = Vec::new();
+            if let Some(ext) = &state.ext_state {
+                let reason = match &state.ext_substate {
+                    Some(sub) => format!("{} ({})", ext, sub),
+                    None => ext.to_string(),
+                };
+                parts.push(reason.red().to_string());
+            }
+            if let Some(sqi) = state.sqi {
+                match state.sqi_max {
+                    Some(max) => parts.push(format!("SQI {}/{}", sqi, max)),
+                    None => parts.push(format!("SQI {}", sqi)),
+                }
+            }
+            if !parts.is_empty() {
+                println!("{}State:    {}", indent, parts.join(", "));
+            }
         }
 
-        #[cfg(target_os = "linux")]
         if verbose {
+            if let Some(modes) = &self.link_modes {
+                let mut port = Vec::new();
+                if let Some(p) = &modes.port {
+                    port.push(p.to_string());
+                }
+                if let Some(xcvr) = &modes.transceiver {
+                    port.push(format!("{} transceiver", xcvr));
+                }
+                match modes.autoneg {
+                    Some(true) => port.push("autoneg on".to_string()),
+                    Some(false) => port.push("autoneg off".to_string()),
+                    None => {}
+                }
+                if let Some(lanes) = modes.lanes {
+                    port.push(format!("{} lanes", lanes));
+                }
+                if !port.is_empty() {
+                    println!("{}Port:     {}", indent, port.join(", "));
+                }
+                if !modes.supported.is_empty() {
+                    println!("{}Supports: {}", indent, modes.supported.join(" "));
+                }
+                if !modes.advertised.is_empty() {
+                    println!("{}Advert:   {}", indent, modes.advertised.join(" "));
+                }
+                if !modes.partner.is_empty() {
+                    print!("{}Partner:  {}", indent, modes.partner.join(" "));
+                    if modes.mismatch {
+                        print!(" {}", "[no common mode]".red());
+                    }
+                    println!();
+                }
+            }
             if !self.features.is_empty() {
                 println!("{}Features: {}", indent, self.features.join(" "));
             }
-            if let Some((rx, tx)) = self.rings {
-                if rx > 0 || tx > 0 {
-                    println!("{}Rings:    RX: {}, TX: {}", indent, rx, tx);
+            if let Some(rings) = self.rings {
+                if rings.rx > 0 || rings.tx > 0 {
+                    println!("{}Rings:    RX: {}, TX: {}", indent, rings.rx, rings.tx);
                 }
             }
-            if let Some((rx, tx, other, combined)) = self.channels {
-                if rx > 0 || tx > 0 || other > 0 || combined > 0 {
+            if let Some(ch) = self.channels {
+                if ch.rx > 0 || ch.tx > 0 || ch.other > 0 || ch.combined > 0 {
                     println!(
                         "{}Channels: RX: {}, TX: {}, Other: {}, Combined: {}",
-                        indent, rx, tx, other, combined
+                        indent, ch.rx, ch.tx, ch.other, ch.combined
                     );
                 }
             }
+            for qdisc in &self.qdiscs {
+                println!("{}Qdisc:    {}", indent, qdisc.summary());
+                println!("{}          {}", indent, qdisc_stats(qdisc));
+                for child in &qdisc.children {
+                    let txq = child
+                        .tx_queue
+                        .map_or_else(String::new, |q| format!("TXQ {}: ", q));
+                    println!("{}            {}{}", indent, txq, child.summary());
+                    println!("{}              {}", indent, qdisc_stats(child));
+                }
+            }
+            if !self.bridge_vlans.is_empty() {
+                println!(
+                    "{}Br VLANs: {}",
+                    indent,
+                    linkinfo::bridge_vlans_str(&self.bridge_vlans)
+                );
+            }
         }
 
         if let Some(stats) = &self.stats {
@@ -411,8 +836,186 @@ impl CollectedInterface {
                     indent, stats.rx_bytes, stats.rx_packets, stats.tx_bytes, stats.tx_packets
                 );
             }
+
+            // Errors and drops are always listed in verbose mode, and otherwise
+            // only when one of them is not zero.
+            let rx = [
+                ("errors", Some(stats.rx_errors)),
+                ("dropped", Some(stats.rx_dropped)),
+                ("missed", stats.rx_missed),
+                ("frame", Some(stats.rx_frame)),
+                ("fifo", Some(stats.rx_fifo)),
+                ("nohandler", stats.rx_nohandler),
+                ("otherhost", stats.rx_otherhost_dropped),
+            ];
+            let tx = [
+                ("errors", Some(stats.tx_errors)),
+                ("dropped", Some(stats.tx_dropped)),
+                ("carrier", Some(stats.tx_carrier)),
+                ("fifo", Some(stats.tx_fifo)),
+                ("collisions", Some(stats.tx_collisions)),
+            ];
+            let any_error = rx.iter().chain(&tx).any(|(_, v)| v.unwrap_or(0) > 0);
+            if verbose || any_error {
+                println!(
+                    "{}Errors:   RX: {}, TX: {}",
+                    indent,
+                    format_counters(&rx),
+                    format_counters(&tx)
+                );
+            }
+        }
+
+        if let Some(neighbors) = &self.neighbors {
+            if neighbors.is_empty() {
+                println!("{}Neighbors: none", indent);
+            } else {
+                println!("{}Neighbors: {}", indent, neigh::summary(neighbors));
+            }
+            let gateways: Vec<&SmolStr> = self
+                .routes
+                .iter()
+                .filter(|r| r.is_default())
+                .filter_map(|r| r.gateway.as_ref())
+                .collect();
+            for n in neighbors {
+                let is_gateway = gateways.contains(&&n.addr);
+                let mut line = format!(
+                    "{}  {} {} {}",
+                    indent,
+                    n.addr,
+                    n.lladdr.as_deref().unwrap_or("-"),
+                    n.state
+                );
+                if n.router {
+                    line.push_str(" router");
+                }
+                if is_gateway && n.is_unresolved() {
+                    println!(
+                        "{} {}",
+                        line.red(),
+                        "[default gateway unreachable]".red().bold()
+                    );
+                } else if is_gateway {
+                    println!("{} {}", line, "[default gateway]".bright_black());
+                } else {
+                    println!("{}", line);
+                }
+            }
+        }
+
+        if let Some(nic_stats) = self.nic_stats.as_ref().filter(|s| !s.is_empty()) {
+            println!("{}NIC stats:", indent);
+            for c in &nic_stats.counters {
+                println!("{}  {}: {}", indent, c.name, c.value);
+            }
+            for q in &nic_stats.queues {
+                let counters: Vec<String> = q
+                    .counters
+                    .iter()
+                    .map(|c| format!("{}={}", c.name, c.value))
+                    .collect();
+                let label = if q.direction.is_empty() {
+                    format!("queue {}", q.queue)
+                } else {
+                    format!("{} queue {}", q.direction, q.queue)
+                };
+                println!(
+                    "{}  {}: {}",
+                    indent,
+                    label.bright_black(),
+                    counters.join(", ")
+                );
+            }
         }
 
         println!();
     }
 }
+
+/// One line per VF, with the netlink settings when the driver reports them
+/// and the VF netdev found through sysfs, followed by the VF counters.
+fn print_vfs(vfs: &[VfInfo], sriov: &pci_utils::Sriov, indent: &str) {
+    let netdev_str = |index: u32| match sriov.vfs.iter().find(|vf| vf.index == index) {
+        Some(vf) => format!(
+            " {} ({})",
+            vf.netdev.as_deref().unwrap_or("-").blue(),
+            vf.pci_address
+        ),
+        None => String::new(),
+    };
+
+    if vfs.is_empty() {
+        for vf in &sriov.vfs {
+            println!("{}  VF {}:{}", indent, vf.index, netdev_str(vf.index));
+        }
+        return;
+    }
+    for vf in vfs {
+        println!(
+            "{}  VF {}:{} {} {}",
+            indent,
+            vf.vf,
+            netdev_str(vf.vf),
+            vf.mac.as_deref().unwrap_or("-"),
+            vf.details_str().dimmed()
+        );
+        if let Some(stats) = &vf.stats {
+            println!(
+                "{}    RX: {} bytes ({} pkts, {} dropped), TX: {} bytes ({} pkts, {} dropped)",
+                indent,
+                stats.rx_bytes,
+                stats.rx_packets,
+                stats.rx_dropped,
+                stats.tx_bytes,
+                stats.tx_packets,
+                stats.tx_dropped
+            );
+        }
+    }
+}
+
+/// Join `value label` pairs, red when non-zero. The first two counters
+/// (errors and dropped) are always shown, the others only when non-zero.
+fn format_counters(counters: &[(&str, Option<u64>)]) -> String {
+    counters
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &(label, value))| match value {
+            Some(0) if i < 2 => Some(format!("{} {}", 0, label)),
+            Some(v) if v > 0 => Some(format!("{} {}", v, label).red().to_string()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// ` valid 3600s preferred 1800s`, or nothing for permanent addresses.
+fn lifetimes_str(valid: Option<u32>, preferred: Option<u32>) -> String {
+    let mut s = String::new();
+    if let Some(valid) = valid {
+        s.push_str(&format!(" valid {}s", valid));
+    }
+    if let Some(preferred) = preferred {
+        s.push_str(&format!(" preferred {}s", preferred));
+    }
+    s
+}
+
+/// A VLAN QoS map as `ip -d link` prints it, `-` when empty.
+fn qos_str(map: &[SmolStr]) -> String {
+    if map.is_empty() {
+        "-".to_string()
+    } else {
+        map.join(" ")
+    }
+}
+
+/// Qdisc counters, dimmed, or red when the qdisc has dropped packets.
+fn qdisc_stats(qdisc: &Qdisc) -> String {
+    if qdisc.drops > 0 {
+        qdisc.stats_str().red().to_string()
+    } else {
+        qdisc.stats_str().dimmed().to_string()
+    }
+}
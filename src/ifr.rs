@@ -1,17 +1,13 @@
 use libc::{c_char, c_int, c_ulong, c_void};
 use nix::sys::socket::{socket, AddressFamily, SockFlag, SockType};
+use serde::Serialize;
 use smol_str::SmolStr;
 use std::io;
 use std::mem;
 use std::os::fd::{AsRawFd, OwnedFd};
 
-#[cfg(target_os = "linux")]
-use futures::stream::TryStreamExt;
-
 // IOCTL Constants
 #[cfg(target_os = "linux")]
-pub const SIOCGIFHWADDR: c_ulong = 0x8927;
-#[cfg(target_os = "linux")]
 pub const SIOCGIFMTU: c_ulong = 0x8921;
 #[cfg(target_os = "linux")]
 pub const SIOCGIFMETRIC: c_ulong = 0x891d;
@@ -31,9 +27,18 @@ pub const SIOCGIFMETRIC: c_ulong = 0;
 // Ethtool Constants
 #[cfg(target_os = "linux")]
 pub const ETHTOOL_GDRVINFO: u32 = 0x00000003;
+#[cfg(target_os = "linux")]
+pub const ETHTOOL_GSTRINGS: u32 = 0x0000001b;
+#[cfg(target_os = "linux")]
+pub const ETHTOOL_GSTATS: u32 = 0x0000001d;
+#[cfg(target_os = "linux")]
+pub const ETH_SS_STATS: u32 = 1;
+#[cfg(target_os = "linux")]
+pub const ETH_GSTRING_LEN: usize = 32;
 
 // If flags
-pub const IFF_UP: i16 = 0x1;
+pub const IFF_UP: u32 = 0x1;
+pub const IFF_RUNNING: u32 = 0x40;
 
 // Structs
 
@@ -101,22 +106,101 @@ pub struct EthtoolDrvInfo {
     pub regdump_len: u32,
 }
 
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Inet4Addr {
+    pub addr: SmolStr,
+    pub mask: Option<SmolStr>,
+    pub prefix: Option<u32>,    // None when the netmask is unknown
+    pub label: Option<SmolStr>, // legacy alias such as eth0:1, when not the interface name
+    pub broadcast: Option<SmolStr>,
+    pub peer: Option<SmolStr>,  // point-to-point peer
+    pub flags: Vec<SmolStr>,    // IFA_F_* names: secondary, noprefixroute...
+    pub valid_lft: Option<u32>, // seconds, None when forever (or unknown)
+    pub preferred_lft: Option<u32>,
+    pub proto: Option<SmolStr>, // IFA_PROTO: who added the address
+}
+
+impl Inet4Addr {
+    /// `addr/prefix`, or just the address when the prefix is unknown.
+    pub fn cidr(&self) -> String {
+        match self.prefix {
+            Some(prefix) => format!("{}/{}", self.addr, prefix),
+            None => self.addr.to_string(),
+        }
+    }
+
+    pub fn is_secondary(&self) -> bool {
+        self.flags.iter().any(|f| f == "secondary")
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct Rings {
+    pub rx: u32,
+    pub tx: u32,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct Channels {
+    pub rx: u32,
+    pub tx: u32,
+    pub other: u32,
+    pub combined: u32,
+}
+
+/// Port and link modes reported by ethtool. The mode lists only hold speed
+/// modes (e.g. `10000baseSR/Full`).
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LinkModes {
+    pub port: Option<SmolStr>,
+    pub transceiver: Option<SmolStr>,
+    pub autoneg: Option<bool>,
+    pub lanes: Option<u32>,
+    pub supported: Vec<SmolStr>,
+    pub advertised: Vec<SmolStr>,
+    pub partner: Vec<SmolStr>,
+    pub mismatch: bool, // we and the link partner advertise no mode in common
+}
+
+/// Carrier and diagnostics reported by the ethtool LINKSTATE message.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LinkState {
+    pub link: Option<bool>,
+    pub ext_state: Option<SmolStr>, // why the link is down, e.g. "no cable"
+    pub ext_substate: Option<SmolStr>, // e.g. "no partner detected"
+    pub sqi: Option<u32>,           // signal quality index, 0..=sqi_max
+    pub sqi_max: Option<u32>,
+    pub down_count: Option<u32>, // link down events
+}
+
+/// Link mode, ring, channel and feature data reported by ethtool for one device.
+#[derive(Debug, Clone, Default)]
+pub struct EthtoolInfo {
+    pub media: Option<SmolStr>,
+    pub speed: Option<u32>, // Mb/s
+    pub link_modes: Option<LinkModes>,
+    pub link_state: Option<LinkState>,
+    pub rings: Option<Rings>,
+    pub channels: Option<Channels>,
+    pub features: Option<Vec<SmolStr>>,
+}
+
 // IOCTL Functions
 
 #[cfg(target_os = "linux")]
 nix::ioctl_write_ptr_bad!(ioctl_ethtool, SIOCETHTOOL, IfReq);
-#[cfg(target_os = "linux")]
-nix::ioctl_read_bad!(ioctl_get_hwaddr, SIOCGIFHWADDR, IfReq);
 nix::ioctl_read_bad!(ioctl_get_mtu, SIOCGIFMTU, IfReq);
 nix::ioctl_read_bad!(ioctl_get_metric, SIOCGIFMETRIC, IfReq);
 
 pub struct Interface {
     name: SmolStr,
     sock: OwnedFd,
+    flags: u32,
 }
 
 impl Interface {
-    pub fn new(name: &str) -> io::Result<Self> {
+    /// `flags` are the interface flags taken from the namespace link snapshot.
+    pub fn new(name: &str, flags: u32) -> io::Result<Self> {
         // Create a dummy socket for ioctls
         let sock = socket(
             AddressFamily::Inet,
@@ -129,191 +213,24 @@ impl Interface {
         Ok(Self {
             name: SmolStr::from(name),
             sock,
+            flags,
         })
     }
 
-    pub fn flags(&self) -> io::Result<i16> {
-        let addrs = nix::ifaddrs::getifaddrs()?;
-        for ifa in addrs {
-            if ifa.interface_name == self.name {
-                return Ok(ifa.flags.bits() as i16);
-            }
-        }
-        Err(io::Error::new(
-            io::ErrorKind::NotFound,
-            "Interface not found",
-        ))
+    pub fn flags(&self) -> u32 {
+        self.flags
     }
 
     pub fn is_up(&self) -> bool {
-        self.flags().map(|f| f & IFF_UP != 0).unwrap_or(false)
+        self.flags() & IFF_UP != 0
     }
 
-    #[allow(dead_code)]
     pub fn is_running(&self) -> bool {
-        // IFF_RUNNING is 0x40
-        self.flags().map(|f| f & 0x40 != 0).unwrap_or(false)
+        self.flags() & IFF_RUNNING != 0
     }
 
-    pub fn flags_str(&self) -> SmolStr {
-        let flags = match self.flags() {
-            Ok(f) => f as u16,
-            Err(_) => return SmolStr::default(),
-        };
-
-        let mut ret = Vec::new();
-
-        #[cfg(target_os = "macos")]
-        {
-            // macOS / BSD flags
-            if flags & 0x1 != 0 {
-                ret.push("UP");
-            }
-            if flags & 0x2 != 0 {
-                ret.push("BROADCAST");
-            }
-            if flags & 0x4 != 0 {
-                ret.push("DEBUG");
-            }
-            if flags & 0x8 != 0 {
-                ret.push("LOOPBACK");
-            }
-            if flags & 0x10 != 0 {
-                ret.push("POINTOPOINT");
-            }
-            if flags & 0x20 != 0 {
-                ret.push("SMART");
-            }
-            if flags & 0x40 != 0 {
-                ret.push("RUNNING");
-            }
-            if flags & 0x80 != 0 {
-                ret.push("NOARP");
-            }
-            if flags & 0x100 != 0 {
-                ret.push("PROMISC");
-            }
-            if flags & 0x200 != 0 {
-                ret.push("ALLMULTI");
-            }
-            if flags & 0x400 != 0 {
-                ret.push("OACTIVE");
-            }
-            if flags & 0x800 != 0 {
-                ret.push("SIMPLEX");
-            }
-            if flags & 0x1000 != 0 {
-                ret.push("LINK0");
-            }
-            if flags & 0x2000 != 0 {
-                ret.push("LINK1");
-            }
-            if flags & 0x4000 != 0 {
-                ret.push("LINK2");
-            }
-            if flags & 0x8000 != 0 {
-                ret.push("MULTICAST");
-            }
-        }
-
-        #[cfg(not(target_os = "macos"))]
-        {
-            // Standard Linux-like flags
-            if flags & 0x1 != 0 {
-                ret.push("UP");
-            }
-            if flags & 0x2 != 0 {
-                ret.push("BROADCAST");
-            }
-            if flags & 0x4 != 0 {
-                ret.push("DEBUG");
-            }
-            if flags & 0x8 != 0 {
-                ret.push("LOOPBACK");
-            }
-            if flags & 0x10 != 0 {
-                ret.push("PTP");
-            }
-            if flags & 0x20 != 0 {
-                ret.push("NOTRAILERS");
-            }
-            if flags & 0x40 != 0 {
-                ret.push("RUNNING");
-            }
-            if flags & 0x80 != 0 {
-                ret.push("NOARP");
-            }
-            if flags & 0x100 != 0 {
-                ret.push("PROMISC");
-            }
-            if flags & 0x200 != 0 {
-                ret.push("ALLMULTI");
-            }
-            if flags & 0x400 != 0 {
-                ret.push("MASTER");
-            }
-            if flags & 0x800 != 0 {
-                ret.push("SLAVE");
-            }
-            if flags & 0x1000 != 0 {
-                ret.push("MULTICAST");
-            }
-            if flags & 0x2000 != 0 {
-                ret.push("PORTSEL");
-            }
-            if flags & 0x4000 != 0 {
-                ret.push("AUTOMEDIA");
-            }
-            if flags & 0x8000 != 0 {
-                ret.push("DYNAMIC");
-            }
-        }
-
-        SmolStr::from(ret.join(" "))
-    }
-
-    #[cfg(target_os = "linux")]
-    pub fn mac(&self) -> io::Result<SmolStr> {
-        use smol_str::format_smolstr;
-
-        let mut req = IfReq::new(&self.name);
-        unsafe { ioctl_get_hwaddr(self.sock.as_raw_fd(), &mut req) }
-            .map_err(|e| io::Error::from_raw_os_error(e as i32))?;
-        let addr = unsafe { req.ifr_ifru.ifru_hwaddr.sa_data };
-        // sa_data is [i8; 14]. MAC is first 6.
-        Ok(format_smolstr!(
-            "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
-            addr[0],
-            addr[1],
-            addr[2],
-            addr[3],
-            addr[4],
-            addr[5]
-        ))
-    }
-
-    #[cfg(not(target_os = "linux"))]
-    pub fn mac(&self) -> io::Result<SmolStr> {
-        let addrs = nix::ifaddrs::getifaddrs()?;
-        for ifa in addrs {
-            if ifa.interface_name == self.name {
-                if let Some(address) = ifa.address {
-                    if let Some(link) = address.as_link_addr() {
-                        if let Some(addr) = link.addr() {
-                            use smol_str::format_smolstr;
-
-                            let s = addr
-                                .iter()
-                                .map(|b| format_smolstr!("{:02x}", b))
-                                .collect::<Vec<_>>()
-                                .join(":");
-                            return Ok(s.into());
-                        }
-                    }
-                }
-            }
-        }
-        Ok(SmolStr::from(""))
+    pub fn flag_names(&self) -> Vec<SmolStr> {
+        flag_names(self.flags())
     }
 
     pub fn mtu(&self) -> io::Result<i32> {
@@ -337,58 +254,6 @@ impl Interface {
         }
     }
 
-    // Inet addrs (using nix::ifaddrs is easier here, as C++ uses getifaddrs)
-    pub fn inet_addrs(&self) -> Vec<(SmolStr, SmolStr, i32)> {
-        let mut ret = Vec::new();
-        if let Ok(addrs) = nix::ifaddrs::getifaddrs() {
-            for ifa in addrs {
-                if ifa.interface_name == self.name {
-                    if let Some(address) = ifa.address {
-                        if let Some(sockaddr) = address.as_sockaddr_in() {
-                            let ip_u32 = sockaddr.ip();
-                            let ip = std::net::Ipv4Addr::from(ip_u32);
-
-                            let mask_opt = ifa
-                                .netmask
-                                .as_ref()
-                                .and_then(|a| a.as_sockaddr_in().map(|s| s.ip()));
-
-                            if let Some(mask_u32) = mask_opt {
-                                let mask_ip = std::net::Ipv4Addr::from(mask_u32);
-                                // Mask is u32.
-                                let prefix = u32::from(mask_ip).count_ones() as i32;
-
-                                ret.push((
-                                    SmolStr::from(ip.to_string()),
-                                    SmolStr::from(mask_ip.to_string()),
-                                    prefix,
-                                ));
-                            } else {
-                                ret.push((
-                                    SmolStr::from(ip.to_string()),
-                                    SmolStr::new_static(""),
-                                    0,
-                                ));
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        ret
-    }
-
-    /// Get link status using ethtool
-    #[cfg(target_os = "linux")]
-    pub fn ethtool_link(&self) -> io::Result<bool> {
-        Ok(self.is_running())
-    }
-
-    #[cfg(not(target_os = "linux"))]
-    pub fn ethtool_link(&self) -> io::Result<bool> {
-        Ok(self.is_running())
-    }
-
     /// Get driver information using ethtool ioctl
     #[cfg(target_os = "linux")]
     pub fn ethtool_drvinfo(&self) -> io::Result<EthtoolDrvInfo> {
@@ -413,230 +278,183 @@ impl Interface {
         ))
     }
 
-    /// Get media/link information using ethtool
+    /// Get the driver-specific statistics (`ethtool -S`) as name/value pairs.
+    /// `n_stats` is the count reported by `ethtool_drvinfo`.
     #[cfg(target_os = "linux")]
-    pub fn media(&self) -> io::Result<SmolStr> {
-        let rt = tokio::runtime::Builder::new_current_thread()
-            .enable_io()
-            .build()
-            .map_err(io::Error::other)?;
-
-        rt.block_on(async {
-            let (connection, mut handle, _) =
-                ethtool::new_connection().map_err(io::Error::other)?;
-
-            tokio::spawn(connection);
-
-            let mut link_mode_handle = handle
-                .link_mode()
-                .get(Some(self.name.as_str()))
-                .execute()
-                .await;
-
-            if let Ok(Some(msg)) = link_mode_handle.try_next().await {
-                use ethtool::{EthtoolAttr, EthtoolLinkModeAttr, EthtoolLinkModeDuplex};
-
-                let mut speed: u32 = 0;
-                let mut duplex_str = "unknown";
-
-                for nla in &msg.payload.nlas {
-                    if let EthtoolAttr::LinkMode(attr) = nla {
-                        match attr {
-                            EthtoolLinkModeAttr::Speed(s) => speed = *s,
-                            EthtoolLinkModeAttr::Duplex(d) => {
-                                duplex_str = match d {
-                                    EthtoolLinkModeDuplex::Full => "full",
-                                    EthtoolLinkModeDuplex::Half => "half",
-                                    _ => "unknown",
-                                };
-                            }
-                            _ => {}
-                        }
-                    }
-                }
-
-                if speed == 0 || speed == 0xFFFF || speed == 0xFFFFFFFF {
-                    return Ok(SmolStr::from("TP (unknown speed)"));
-                }
-                return Ok(SmolStr::from(format!("TP {}Mb/s {}", speed, duplex_str)));
-            }
-
-            Ok(SmolStr::new_static("unknown"))
-        })
-    }
+    pub fn ethtool_stats(&self, n_stats: u32) -> io::Result<Vec<(SmolStr, u64)>> {
+        let n = n_stats as usize;
+        if n == 0 {
+            return Ok(Vec::new());
+        }
 
-    #[cfg(not(target_os = "linux"))]
-    pub fn media(&self) -> io::Result<SmolStr> {
-        Ok(SmolStr::new_static("unknown"))
-    }
+        // struct ethtool_gstrings { u32 cmd, string_set, len; u8 data[] },
+        // in a u64 buffer to keep it aligned.
+        let mut strings = vec![0u64; (12 + n * ETH_GSTRING_LEN).div_ceil(8)];
+        let header = strings.as_mut_ptr() as *mut u32;
+        unsafe {
+            *header = ETHTOOL_GSTRINGS;
+            *header.add(1) = ETH_SS_STATS;
+            *header.add(2) = n_stats;
+        }
+        self.ethtool_ioctl(strings.as_mut_ptr() as *mut c_void)?;
+        if unsafe { *header.add(2) } != n_stats {
+            return Err(io::Error::other("statistics changed while reading"));
+        }
 
-    /// Get ring parameters (RX/TX ring sizes)
-    #[cfg(target_os = "linux")]
-    pub fn ethtool_rings(&self) -> io::Result<(u32, u32)> {
-        let rt = tokio::runtime::Builder::new_current_thread()
-            .enable_io()
-            .build()
-            .map_err(io::Error::other)?;
-
-        rt.block_on(async {
-            let (connection, mut handle, _) =
-                ethtool::new_connection().map_err(io::Error::other)?;
-
-            tokio::spawn(connection);
-
-            let mut ring_handle = handle.ring().get(Some(self.name.as_str())).execute().await;
-
-            if let Ok(Some(msg)) = ring_handle.try_next().await {
-                use ethtool::{EthtoolAttr, EthtoolRingAttr};
-
-                let mut rx: u32 = 0;
-                let mut tx: u32 = 0;
-
-                for nla in &msg.payload.nlas {
-                    if let EthtoolAttr::Ring(attr) = nla {
-                        match attr {
-                            EthtoolRingAttr::Rx(val) => rx = *val,
-                            EthtoolRingAttr::Tx(val) => tx = *val,
-                            _ => {}
-                        }
-                    }
-                }
-
-                Ok((rx, tx))
-            } else {
-                Ok((0, 0))
-            }
-        })
+        // struct ethtool_stats { u32 cmd, n_stats; u64 data[] }
+        let mut values = vec![0u64; 1 + n];
+        let header = values.as_mut_ptr() as *mut u32;
+        unsafe {
+            *header = ETHTOOL_GSTATS;
+            *header.add(1) = n_stats;
+        }
+        self.ethtool_ioctl(values.as_mut_ptr() as *mut c_void)?;
+        if unsafe { *header.add(1) } != n_stats {
+            return Err(io::Error::other("statistics changed while reading"));
+        }
+
+        let names = unsafe {
+            std::slice::from_raw_parts((strings.as_ptr() as *const u8).add(12), n * ETH_GSTRING_LEN)
+        };
+        Ok(names
+            .chunks(ETH_GSTRING_LEN)
+            .zip(&values[1..])
+            .map(|(raw, &value)| {
+                let len = raw.iter().position(|&b| b == 0).unwrap_or(raw.len());
+                (SmolStr::from(String::from_utf8_lossy(&raw[..len])), value)
+            })
+            .collect())
     }
 
     #[cfg(not(target_os = "linux"))]
-    pub fn ethtool_rings(&self) -> io::Result<(u32, u32)> {
+    pub fn ethtool_stats(&self, _n_stats: u32) -> io::Result<Vec<(SmolStr, u64)>> {
         Err(io::Error::new(
             io::ErrorKind::Unsupported,
-            "Ring info not available on this OS",
+            "Not supported on this OS",
         ))
     }
 
-    /// Get channel parameters (number of RX/TX queues)
     #[cfg(target_os = "linux")]
-    pub fn ethtool_channels(&self) -> io::Result<(u32, u32, u32, u32)> {
-        let rt = tokio::runtime::Builder::new_current_thread()
-            .enable_io()
-            .build()
-            .map_err(io::Error::other)?;
-
-        rt.block_on(async {
-            let (connection, mut handle, _) =
-                ethtool::new_connection().map_err(io::Error::other)?;
-
-            tokio::spawn(connection);
-
-            let mut channel_handle = handle
-                .channel()
-                .get(Some(self.name.as_str()))
-                .execute()
-                .await;
-
-            if let Ok(Some(msg)) = channel_handle.try_next().await {
-                use ethtool::{EthtoolAttr, EthtoolChannelAttr};
-
-                let mut rx: u32 = 0;
-                let mut tx: u32 = 0;
-                let mut other: u32 = 0;
-                let mut combined: u32 = 0;
-
-                for nla in &msg.payload.nlas {
-                    if let EthtoolAttr::Channel(attr) = nla {
-                        match attr {
-                            EthtoolChannelAttr::RxCount(val) => rx = *val,
-                            EthtoolChannelAttr::TxCount(val) => tx = *val,
-                            EthtoolChannelAttr::OtherCount(val) => other = *val,
-                            EthtoolChannelAttr::CombinedCount(val) => combined = *val,
-                            _ => {}
-                        }
-                    }
-                }
-
-                Ok((rx, tx, other, combined))
-            } else {
-                Ok((0, 0, 0, 0))
-            }
-        })
+    fn ethtool_ioctl(&self, data: *mut c_void) -> io::Result<()> {
+        let mut req = IfReq::new(&self.name);
+        req.ifr_ifru.ifru_data = data;
+        unsafe { ioctl_ethtool(self.sock.as_raw_fd(), &req) }
+            .map_err(|e| io::Error::from_raw_os_error(e as i32))?;
+        Ok(())
     }
+}
 
-    #[cfg(not(target_os = "linux"))]
-    pub fn ethtool_channels(&self) -> io::Result<(u32, u32, u32, u32)> {
-        Err(io::Error::new(
-            io::ErrorKind::Unsupported,
-            "Channel info not available on this OS",
-        ))
-    }
+/// Names of the interface flags set in `flags`.
+pub fn flag_names(flags: u32) -> Vec<SmolStr> {
+    let flags = flags as u16;
 
-    /// Get active features/offloads (TSO, GSO, GRO, checksumming, etc.)
-    #[cfg(target_os = "linux")]
-    pub fn ethtool_features(&self) -> io::Result<Vec<SmolStr>> {
-        let rt = tokio::runtime::Builder::new_current_thread()
-            .enable_io()
-            .build()
-            .map_err(io::Error::other)?;
-
-        rt.block_on(async {
-            let (connection, mut handle, _) =
-                ethtool::new_connection().map_err(io::Error::other)?;
-
-            tokio::spawn(connection);
-
-            let mut feature_handle = handle
-                .feature()
-                .get(Some(self.name.as_str()))
-                .execute()
-                .await;
-
-            if let Ok(Some(msg)) = feature_handle.try_next().await {
-                use ethtool::{EthtoolAttr, EthtoolFeatureAttr};
-
-                let mut features = Vec::new();
-
-                for nla in &msg.payload.nlas {
-                    if let EthtoolAttr::Feature(EthtoolFeatureAttr::Active(bits)) = nla {
-                        for bit in bits {
-                            if bit.value {
-                                let feature_name = match bit.name.as_str() {
-                                    "tx-tcp-segmentation" => "tso",
-                                    "tx-generic-segmentation" => "gso",
-                                    "rx-gro" => "gro",
-                                    "rx-lro" => "lro",
-                                    "rx-checksum" => "rx-csum",
-                                    "tx-checksum-ip-generic" => "tx-csum",
-                                    "tx-checksum-ipv4" => "tx-csum-ipv4",
-                                    "tx-checksum-ipv6" => "tx-csum-ipv6",
-                                    "tx-scatter-gather" => "sg",
-                                    "tx-scatter-gather-fraglist" => "sg-frag",
-                                    "tx-vlan-hw-insert" => "tx-vlan",
-                                    "rx-vlan-hw-parse" => "rx-vlan",
-                                    "highdma" => "highdma",
-                                    "rx-hashing" => "rxhash",
-                                    "rx-ntuple-filter" => "ntuple",
-                                    other => other,
-                                };
-                                features.push(SmolStr::from(feature_name));
-                            }
-                        }
-                    }
-                }
-
-                Ok(features)
-            } else {
-                Ok(Vec::new())
-            }
-        })
+    let mut ret = Vec::new();
+
+    #[cfg(target_os = "macos")]
+    {
+        // macOS / BSD flags
+        if flags & 0x1 != 0 {
+            ret.push("UP");
+        }
+        if flags & 0x2 != 0 {
+            ret.push("BROADCAST");
+        }
+        if flags & 0x4 != 0 {
+            ret.push("DEBUG");
+        }
+        if flags & 0x8 != 0 {
+            ret.push("LOOPBACK");
+        }
+        if flags & 0x10 != 0 {
+            ret.push("POINTOPOINT");
+        }
+        if flags & 0x20 != 0 {
+            ret.push("SMART");
+        }
+        if flags & 0x40 != 0 {
+            ret.push("RUNNING");
+        }
+        if flags & 0x80 != 0 {
+            ret.push("NOARP");
+        }
+        if flags & 0x100 != 0 {
+            ret.push("PROMISC");
+        }
+        if flags & 0x200 != 0 {
+            ret.push("ALLMULTI");
+        }
+        if flags & 0x400 != 0 {
+            ret.push("OACTIVE");
+        }
+        if flags & 0x800 != 0 {
+            ret.push("SIMPLEX");
+        }
+        if flags & 0x1000 != 0 {
+            ret.push("LINK0");
+        }
+        if flags & 0x2000 != 0 {
+            ret.push("LINK1");
+        }
+        if flags & 0x4000 != 0 {
+            ret.push("LINK2");
+        }
+        if flags & 0x8000 != 0 {
+            ret.push("MULTICAST");
+        }
     }
 
-    #[cfg(not(target_os = "linux"))]
-    pub fn ethtool_features(&self) -> io::Result<Vec<SmolStr>> {
-        Err(io::Error::new(
-            io::ErrorKind::Unsupported,
-            "Feature info not available on this OS",
-        ))
+    #[cfg(not(target_os = "macos"))]
+    {
+        // Standard Linux-like flags
+        if flags & 0x1 != 0 {
+            ret.push("UP");
+        }
+        if flags & 0x2 != 0 {
+            ret.push("BROADCAST");
+        }
+        if flags & 0x4 != 0 {
+            ret.push("DEBUG");
+        }
+        if flags & 0x8 != 0 {
+            ret.push("LOOPBACK");
+        }
+        if flags & 0x10 != 0 {
+            ret.push("PTP");
+        }
+        if flags & 0x20 != 0 {
+            ret.push("NOTRAILERS");
+        }
+        if flags & 0x40 != 0 {
+            ret.push("RUNNING");
+        }
+        if flags & 0x80 != 0 {
+            ret.push("NOARP");
+        }
+        if flags & 0x100 != 0 {
+            ret.push("PROMISC");
+        }
+        if flags & 0x200 != 0 {
+            ret.push("ALLMULTI");
+        }
+        if flags & 0x400 != 0 {
+            ret.push("MASTER");
+        }
+        if flags & 0x800 != 0 {
+            ret.push("SLAVE");
+        }
+        if flags & 0x1000 != 0 {
+            ret.push("MULTICAST");
+        }
+        if flags & 0x2000 != 0 {
+            ret.push("PORTSEL");
+        }
+        if flags & 0x4000 != 0 {
+            ret.push("AUTOMEDIA");
+        }
+        if flags & 0x8000 != 0 {
+            ret.push("DYNAMIC");
+        }
     }
+
+    ret.into_iter().map(SmolStr::new_static).collect()
 }
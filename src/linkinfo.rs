@@ -0,0 +1,527 @@
+//! Interface kind and type-specific settings, from IFLA_LINKINFO.
+
+use serde::Serialize;
+use smol_str::SmolStr;
+use std::collections::BTreeMap;
+
+/// The `--kind` name of interfaces that report no kind, such as physical NICs.
+pub const KIND_DEVICE: &str = "device";
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LinkInfo {
+    /// IFLA_INFO_KIND, as accepted by `ip link add type` (veth, bond, vlan...).
+    pub kind: SmolStr,
+    /// Settings of the simpler kinds, e.g. `mode` of a macvlan or `id` of a vxlan.
+    pub details: BTreeMap<SmolStr, SmolStr>,
+    pub vlan: Option<VlanInfo>,
+    pub bond: Option<BondInfo>,
+    pub bridge: Option<BridgeInfo>,
+    pub peer: Option<VethPeer>,
+}
+
+/// The other end of a veth pair, from IFLA_LINK and IFLA_LINK_NETNSID.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VethPeer {
+    /// None when the peer could not be looked up in its namespace.
+    pub name: Option<SmolStr>,
+    pub index: u32,
+    /// Set when the peer lives in another namespace.
+    pub netnsid: Option<i32>,
+    /// Name of that namespace under /var/run/netns, when it has one.
+    pub netns: Option<SmolStr>,
+}
+
+impl std::fmt::Display for VethPeer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "{}", name)?,
+            None => write!(f, "ifindex {}", self.index)?,
+        }
+        match (&self.netns, self.netnsid) {
+            (Some(netns), _) => write!(f, "@{{{}}}", netns),
+            (None, Some(id)) => write!(f, "@{{nsid {}}}", id),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// IFLA_INFO_SLAVE_KIND and IFLA_INFO_SLAVE_DATA: the role of a link
+/// enslaved to a bond (or a bridge), as seen from the link itself.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SlaveInfo {
+    /// Kind of the master (bond, bridge...).
+    pub kind: SmolStr,
+    /// Name of the master, from IFLA_MASTER.
+    pub master: Option<SmolStr>,
+    pub bond: Option<BondSlaveInfo>,
+    pub bridge: Option<BridgePortInfo>,
+}
+
+/// An 802.1Q or 802.1ad sub-interface (IFLA_INFO_DATA of kind `vlan`).
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VlanInfo {
+    pub id: u16,
+    /// 802.1Q (0x8100) or 802.1ad (0x88a8).
+    pub protocol: SmolStr,
+    /// The lower device, from IFLA_LINK; None when it lives in another namespace.
+    pub parent: Option<SmolStr>,
+    /// reorder_hdr, gvrp, loose_binding, mvrp, bridge_binding.
+    pub flags: Vec<SmolStr>,
+    /// 802.1p PCP -> skb priority of received frames, as `from:to` pairs.
+    pub ingress_qos: Vec<SmolStr>,
+    /// skb priority -> 802.1p PCP of transmitted frames.
+    pub egress_qos: Vec<SmolStr>,
+}
+
+/// Settings and state of a bonding master (IFLA_INFO_DATA of kind `bond`).
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BondInfo {
+    pub mode: SmolStr,
+    /// Current active slave (active-backup, balance-tlb and balance-alb).
+    pub active_slave: Option<SmolStr>,
+    #[serde(skip)]
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub active_slave_index: Option<u32>,
+    pub miimon: Option<u32>, // ms
+    pub updelay: Option<u32>,
+    pub downdelay: Option<u32>,
+    pub xmit_hash_policy: Option<SmolStr>,
+    pub lacp_rate: Option<SmolStr>,
+    /// ID of the active 802.3ad aggregator.
+    pub aggregator: Option<u16>,
+}
+
+/// State of one leg of a bond (IFLA_INFO_SLAVE_DATA of slave kind `bond`).
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BondSlaveInfo {
+    /// active or backup.
+    pub state: SmolStr,
+    /// up, going_down, down or going_back.
+    pub mii_status: SmolStr,
+    pub link_failure_count: u32,
+    /// The MAC address of the slave before it was enslaved.
+    pub perm_hwaddr: Option<SmolStr>,
+    /// 802.3ad aggregator this slave belongs to.
+    pub aggregator: Option<u16>,
+    /// 802.3ad actor and partner operational port state, e.g. `collecting`.
+    pub actor_state: Option<Vec<SmolStr>>,
+    pub partner_state: Option<Vec<SmolStr>>,
+}
+
+/// Settings and STP state of a Linux bridge (IFLA_INFO_DATA of kind `bridge`).
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BridgeInfo {
+    /// `priority.mac`, as `ip -d link` prints it (e.g. 8000.66:99:33:e4:07:e7).
+    pub bridge_id: Option<SmolStr>,
+    pub root_id: Option<SmolStr>,
+    pub root_port: Option<u16>,
+    pub priority: Option<u16>,
+    /// off, kernel or user (an STP daemon such as mstpd).
+    pub stp_state: SmolStr,
+    pub forward_delay: Option<u32>, // s
+    pub ageing_time: Option<u32>,   // s
+    pub vlan_filtering: bool,
+}
+
+/// State and settings of a bridge port (IFLA_INFO_SLAVE_DATA of slave kind `bridge`).
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BridgePortInfo {
+    /// disabled, listening, learning, forwarding or blocking.
+    pub state: SmolStr,
+    pub priority: Option<u16>,
+    pub cost: Option<u32>,
+    pub learning: bool,
+    pub flood: bool, // unknown unicast
+    pub mcast_flood: bool,
+    pub bcast_flood: bool,
+    pub hairpin: bool,
+    pub neigh_suppress: bool,
+}
+
+/// One entry of the bridge VLAN table of a port, with consecutive VLANs that
+/// share the same flags folded into a range.
+#[derive(Debug, Clone, Serialize)]
+pub struct BridgeVlan {
+    pub vid: u16,
+    pub vid_end: u16,
+    pub pvid: bool,
+    pub untagged: bool,
+}
+
+impl BridgeVlan {
+    pub fn range_str(&self) -> String {
+        if self.vid == self.vid_end {
+            self.vid.to_string()
+        } else {
+            format!("{}-{}", self.vid, self.vid_end)
+        }
+    }
+}
+
+/// Append a VLAN range to a table, extending the last entry when it is
+/// contiguous and has the same flags.
+#[cfg(target_os = "linux")]
+pub fn push_bridge_vlan(table: &mut Vec<BridgeVlan>, vlan: BridgeVlan) {
+    match table.last_mut() {
+        Some(last)
+            if last.vid_end.checked_add(1) == Some(vlan.vid)
+                && last.pvid == vlan.pvid
+                && last.untagged == vlan.untagged =>
+        {
+            last.vid_end = vlan.vid_end
+        }
+        _ => table.push(vlan),
+    }
+}
+
+/// The table as comma-separated ranges (`1,10-20,100`) with PVID and
+/// untagged markers.
+pub fn bridge_vlans_str(table: &[BridgeVlan]) -> String {
+    table
+        .iter()
+        .map(|v| {
+            let mut s = v.range_str();
+            if v.pvid {
+                s.push_str(" PVID");
+            }
+            if v.untagged {
+                s.push_str(" untagged");
+            }
+            s
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+impl BridgeInfo {
+    pub fn summary(&self) -> String {
+        let mut s = format!("stp {}", self.stp_state);
+        let mut push = |key: &str, value: Option<String>| {
+            if let Some(value) = value {
+                s.push_str(&format!(" {} {}", key, value));
+            }
+        };
+        push("id", self.bridge_id.as_ref().map(|v| v.to_string()));
+        push("root", self.root_id.as_ref().map(|v| v.to_string()));
+        push(
+            "root_port",
+            self.root_port.filter(|&p| p != 0).map(|v| v.to_string()),
+        );
+        push(
+            "forward_delay",
+            self.forward_delay.map(|v| format!("{}s", v)),
+        );
+        push("ageing_time", self.ageing_time.map(|v| format!("{}s", v)));
+        if self.vlan_filtering {
+            s.push_str(" vlan_filtering");
+        }
+        s
+    }
+}
+
+impl BridgePortInfo {
+    /// `forwarding priority 32 cost 2`, followed by the flags that are set.
+    pub fn summary(&self) -> String {
+        let mut s = self.state.to_string();
+        if let Some(priority) = self.priority {
+            s.push_str(&format!(" priority {}", priority));
+        }
+        if let Some(cost) = self.cost {
+            s.push_str(&format!(" cost {}", cost));
+        }
+        for flag in self.flags() {
+            s.push(' ');
+            s.push_str(flag);
+        }
+        s
+    }
+
+    /// Names of the flags that are on.
+    pub fn flags(&self) -> Vec<&'static str> {
+        [
+            (self.learning, "learning"),
+            (self.flood, "flood"),
+            (self.mcast_flood, "mcast_flood"),
+            (self.bcast_flood, "bcast_flood"),
+            (self.hairpin, "hairpin"),
+            (self.neigh_suppress, "neigh_suppress"),
+        ]
+        .into_iter()
+        .filter(|(set, _)| *set)
+        .map(|(_, name)| name)
+        .collect()
+    }
+}
+
+impl BondInfo {
+    /// `mode 802.3ad miimon 100 ...`, leaving out the settings the kernel did not report.
+    pub fn summary(&self) -> String {
+        let mut s = format!("mode {}", self.mode);
+        let mut push = |key: &str, value: Option<String>| {
+            if let Some(value) = value {
+                s.push_str(&format!(" {} {}", key, value));
+            }
+        };
+        push("miimon", self.miimon.map(|v| v.to_string()));
+        push("updelay", self.updelay.map(|v| v.to_string()));
+        push("downdelay", self.downdelay.map(|v| v.to_string()));
+        push(
+            "xmit_hash_policy",
+            self.xmit_hash_policy.as_ref().map(|v| v.to_string()),
+        );
+        push("lacp_rate", self.lacp_rate.as_ref().map(|v| v.to_string()));
+        push("aggregator", self.aggregator.map(|v| v.to_string()));
+        s
+    }
+}
+
+impl BondSlaveInfo {
+    /// Whether the slave carries traffic of its 802.3ad bond: in sync and both
+    /// collecting and distributing. Always true outside 802.3ad.
+    pub fn lacp_ok(&self) -> bool {
+        self.actor_state.as_ref().is_none_or(|state| {
+            ["synchronization", "collecting", "distributing"]
+                .iter()
+                .all(|flag| state.iter().any(|s| s == flag))
+        })
+    }
+}
+
+impl VlanInfo {
+    /// `id 5 proto 802.1Q`, followed by the flags that are set.
+    pub fn summary(&self) -> String {
+        let mut s = format!("id {} proto {}", self.id, self.protocol);
+        for flag in &self.flags {
+            s.push(' ');
+            s.push_str(flag);
+        }
+        s
+    }
+}
+
+impl LinkInfo {
+    #[cfg(target_os = "linux")]
+    pub fn new(kind: &str) -> Self {
+        Self {
+            kind: SmolStr::from(kind),
+            details: BTreeMap::new(),
+            vlan: None,
+            bond: None,
+            bridge: None,
+            peer: None,
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn set(&mut self, key: &'static str, value: impl ToString) {
+        self.details
+            .insert(SmolStr::new_static(key), SmolStr::from(value.to_string()));
+    }
+
+    /// `key value` pairs, space separated, as `ip -d link` prints them.
+    pub fn details_str(&self) -> String {
+        self.details
+            .iter()
+            .map(|(k, v)| format!("{} {}", k, v))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn macvlan_mode_name(mode: u32) -> &'static str {
+    match mode {
+        1 => "private",
+        2 => "vepa",
+        4 => "bridge",
+        8 => "passthru",
+        16 => "source",
+        _ => "unknown",
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn ipvlan_mode_name(mode: u16) -> &'static str {
+    match mode {
+        0 => "l2",
+        1 => "l3",
+        2 => "l3s",
+        _ => "unknown",
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn vlan_protocol_name(proto: u16) -> SmolStr {
+    match proto {
+        0x8100 => SmolStr::new_static("802.1Q"),
+        0x88a8 => SmolStr::new_static("802.1ad"),
+        _ => smol_str::format_smolstr!("{:#06x}", proto),
+    }
+}
+
+/// Names of the VLAN_FLAG_* bits set in `flags`.
+#[cfg(target_os = "linux")]
+pub fn vlan_flag_names(flags: u32) -> Vec<SmolStr> {
+    [
+        (0x1, "reorder_hdr"),
+        (0x2, "gvrp"),
+        (0x4, "loose_binding"),
+        (0x8, "mvrp"),
+        (0x10, "bridge_binding"),
+    ]
+    .iter()
+    .filter(|(bit, _)| flags & bit != 0)
+    .map(|(_, name)| SmolStr::new_static(name))
+    .collect()
+}
+
+#[cfg(target_os = "linux")]
+pub fn bond_mode_name(mode: u8) -> SmolStr {
+    match mode {
+        0 => SmolStr::new_static("balance-rr"),
+        1 => SmolStr::new_static("active-backup"),
+        2 => SmolStr::new_static("balance-xor"),
+        3 => SmolStr::new_static("broadcast"),
+        4 => SmolStr::new_static("802.3ad"),
+        5 => SmolStr::new_static("balance-tlb"),
+        6 => SmolStr::new_static("balance-alb"),
+        _ => smol_str::format_smolstr!("{}", mode),
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn bond_xmit_hash_policy_name(policy: u8) -> SmolStr {
+    match policy {
+        0 => SmolStr::new_static("layer2"),
+        1 => SmolStr::new_static("layer3+4"),
+        2 => SmolStr::new_static("layer2+3"),
+        3 => SmolStr::new_static("encap2+3"),
+        4 => SmolStr::new_static("encap3+4"),
+        5 => SmolStr::new_static("vlan+srcmac"),
+        _ => smol_str::format_smolstr!("{}", policy),
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn bond_slave_state_name(state: u8) -> SmolStr {
+    match state {
+        0 => SmolStr::new_static("active"),
+        1 => SmolStr::new_static("backup"),
+        _ => smol_str::format_smolstr!("{}", state),
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn bond_mii_status_name(status: u8) -> SmolStr {
+    match status {
+        0 => SmolStr::new_static("up"),
+        1 => SmolStr::new_static("going_down"),
+        2 => SmolStr::new_static("down"),
+        3 => SmolStr::new_static("going_back"),
+        _ => smol_str::format_smolstr!("{}", status),
+    }
+}
+
+/// Names of the 802.3ad port state bits set in `state` (IEEE 802.1AX 6.4.2.3).
+#[cfg(target_os = "linux")]
+pub fn lacp_state_names(state: u8) -> Vec<SmolStr> {
+    [
+        (0x01, "active"),
+        (0x02, "short_timeout"),
+        (0x04, "aggregating"),
+        (0x08, "synchronization"),
+        (0x10, "collecting"),
+        (0x20, "distributing"),
+        (0x40, "defaulted"),
+        (0x80, "expired"),
+    ]
+    .iter()
+    .filter(|(bit, _)| state & bit != 0)
+    .map(|(_, name)| SmolStr::new_static(name))
+    .collect()
+}
+
+#[cfg(target_os = "linux")]
+pub fn stp_state_name(state: u32) -> SmolStr {
+    match state {
+        0 => SmolStr::new_static("off"),
+        1 => SmolStr::new_static("kernel"),
+        2 => SmolStr::new_static("user"),
+        _ => smol_str::format_smolstr!("{}", state),
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn bridge_port_state_name(state: u8) -> SmolStr {
+    match state {
+        0 => SmolStr::new_static("disabled"),
+        1 => SmolStr::new_static("listening"),
+        2 => SmolStr::new_static("learning"),
+        3 => SmolStr::new_static("forwarding"),
+        4 => SmolStr::new_static("blocking"),
+        _ => smol_str::format_smolstr!("{}", state),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vlan(vid: u16, vid_end: u16, pvid: bool, untagged: bool) -> BridgeVlan {
+        BridgeVlan {
+            vid,
+            vid_end,
+            pvid,
+            untagged,
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn push_bridge_vlan_merges_contiguous() {
+        let mut table = Vec::new();
+        for vid in 10..=20 {
+            push_bridge_vlan(&mut table, vlan(vid, vid, false, false));
+        }
+        push_bridge_vlan(&mut table, vlan(21, 30, false, false));
+        assert_eq!(table.len(), 1);
+        assert_eq!((table[0].vid, table[0].vid_end), (10, 30));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn push_bridge_vlan_keeps_gaps_and_flags_apart() {
+        let mut table = Vec::new();
+        push_bridge_vlan(&mut table, vlan(1, 1, true, true));
+        // Contiguous, but different flags.
+        push_bridge_vlan(&mut table, vlan(2, 2, false, false));
+        // Same flags, but not contiguous.
+        push_bridge_vlan(&mut table, vlan(4, 4, false, false));
+        push_bridge_vlan(&mut table, vlan(5, 5, false, true));
+        let ranges: Vec<_> = table.iter().map(|v| v.range_str()).collect();
+        assert_eq!(ranges, ["1", "2", "4", "5"]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn push_bridge_vlan_does_not_wrap() {
+        let mut table = vec![vlan(1, u16::MAX, false, false)];
+        push_bridge_vlan(&mut table, vlan(0, 0, false, false));
+        assert_eq!(table.len(), 2);
+    }
+
+    #[test]
+    fn bridge_vlans_str_markers() {
+        let table = [
+            vlan(1, 1, true, true),
+            vlan(10, 20, false, false),
+            vlan(30, 30, false, true),
+            vlan(40, 41, true, false),
+        ];
+        assert_eq!(
+            bridge_vlans_str(&table),
+            "1 PVID untagged,10-20,30 untagged,40-41 PVID"
+        );
+        assert_eq!(bridge_vlans_str(&[]), "");
+    }
+}
@@ -261,8 +261,14 @@ pub fn get_stats(if_name: &str) -> Option<Stats> {
                     stats = Some(Stats {
                         rx_bytes: d.ifi_ibytes as u64,
                         rx_packets: d.ifi_ipackets as u64,
+                        rx_errors: d.ifi_ierrors as u64,
+                        rx_dropped: d.ifi_iqdrops as u64,
+                        rx_multicast: d.ifi_imcasts as u64,
                         tx_bytes: d.ifi_obytes as u64,
                         tx_packets: d.ifi_opackets as u64,
+                        tx_errors: d.ifi_oerrors as u64,
+                        tx_collisions: d.ifi_collisions as u64,
+                        ..Default::default()
                     });
                     break;
                 }
@@ -5,17 +5,33 @@ use nix::sched::{setns, CloneFlags};
 use owo_colors::OwoColorize;
 use rayon::prelude::*;
 use smol_str::SmolStr;
+use std::collections::HashMap;
 #[cfg(target_os = "linux")]
 use std::fs;
+#[cfg(not(target_os = "macos"))]
+use std::sync::OnceLock;
 
+mod bpf;
+#[cfg(target_os = "linux")]
+mod ethnl;
+mod fields;
 mod filter;
 mod ifr;
+mod linkinfo;
 #[cfg(target_os = "macos")]
 mod macos;
+mod neigh;
+#[cfg(target_os = "linux")]
+mod netlink;
+mod nic_stats;
+mod output;
 mod pci_utils;
 mod proc;
+mod route;
+mod sriov;
+mod tc;
 
-use filter::{CollectedInterface, Matcher};
+use filter::{CollectedInterface, Matcher, Prefilter};
 
 #[derive(Parser)]
 #[command(
@@ -49,6 +65,45 @@ struct Cli {
     #[arg(short = 'i', long = "ignore-case")]
     ignore_case: bool,
 
+    /// Print the report as a JSON document
+    #[arg(short = 'j', long = "json", conflicts_with = "brief")]
+    json: bool,
+
+    /// Print one line per interface
+    #[arg(short = 'b', long = "brief", conflicts_with_all = ["format", "fields"])]
+    brief: bool,
+
+    /// Print each interface with a template, e.g. '{name}\t{driver}\t{pci}'
+    #[arg(long = "format", value_name = "TEMPLATE", conflicts_with_all = ["json", "fields"])]
+    format: Option<String>,
+
+    /// Print the given comma-separated fields, tab separated (e.g. name,mac,ipv4)
+    #[arg(long = "fields", value_name = "LIST", conflicts_with = "json")]
+    fields: Option<String>,
+
+    /// List the field names accepted by --format and --fields
+    #[arg(long = "list-fields")]
+    list_fields: bool,
+
+    /// Show driver statistics (ethtool -S), optionally only those containing FILTER
+    #[arg(
+        long = "stats",
+        value_name = "FILTER",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = ""
+    )]
+    stats: Option<String>,
+
+    /// Show the neighbor (ARP/NDP) entries of each interface
+    #[arg(long = "neigh")]
+    neigh: bool,
+
+    /// Show only interfaces of the given kinds, e.g. vlan,bond ("device" for
+    /// interfaces without a kind, such as physical NICs)
+    #[arg(long = "kind", value_name = "LIST", value_delimiter = ',')]
+    kind: Vec<SmolStr>,
+
     /// Interface list / Keywords
     #[arg(trailing_var_arg = true)]
     keywords: Vec<SmolStr>,
@@ -57,6 +112,17 @@ struct Cli {
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
+    if cli.list_fields {
+        fields::print_list();
+        return Ok(());
+    }
+
+    let template = match (&cli.format, &cli.fields) {
+        (Some(format), _) => Some(fields::Template::parse(format)?),
+        (None, Some(list)) => Some(fields::Template::from_fields(list)?),
+        (None, None) => None,
+    };
+
     let matcher = Matcher {
         keywords: cli.keywords.into_iter().collect(),
         ipv4: cli.ipv4,
@@ -64,38 +130,47 @@ fn main() -> Result<()> {
         running: cli.running,
         ignore_case: cli.ignore_case,
         all: cli.all,
+        kinds: cli.kind,
     };
 
+    let details = cli.verbose || cli.json || template.as_ref().is_some_and(|t| t.needs_details());
+
+    // Enumerated on first use, from whichever namespace gets there first.
     #[cfg(not(target_os = "macos"))]
-    let pci_devices = pci_utils::get_pci_devices().unwrap_or_default();
+    let pci_devices = OnceLock::new();
 
-    let all_interfaces = proc::get_if_list()?;
+    let namespaces = proc::get_netns_list()?;
 
     #[cfg(target_os = "linux")]
     let original_ns = fs::File::open("/proc/self/ns/net").ok();
 
-    let results: Vec<_> = all_interfaces
+    let per_ns: Vec<Vec<(SmolStr, Result<CollectedInterface>)>> = namespaces
         .par_iter()
-        .map(|nic| {
+        .map(|netns| {
             // --- Namespace Switching (Linux specific) ---
             #[cfg(target_os = "linux")]
             let mut switched_ns = false;
             #[cfg(target_os = "linux")]
-            if let (Some(ns_name), Some(_)) = (&nic.netns, &original_ns) {
-                if let Ok(ns_file) = fs::File::open(format!("/var/run/netns/{}", ns_name)) {
-                    if setns(ns_file, CloneFlags::CLONE_NEWNET).is_ok() {
-                        switched_ns = true;
-                    }
+            if let Some(ns_name) = netns {
+                let entered = original_ns.is_some()
+                    && fs::File::open(format!("/var/run/netns/{}", ns_name))
+                        .map(|ns_file| setns(ns_file, CloneFlags::CLONE_NEWNET).is_ok())
+                        .unwrap_or(false);
+                if !entered {
+                    return Vec::new();
                 }
+                switched_ns = true;
             }
 
-            let result: Result<CollectedInterface> = (|| {
+            let result = collect_namespace(
+                netns.as_ref(),
+                &matcher,
+                details,
+                cli.stats.as_deref(),
+                cli.neigh,
                 #[cfg(not(target_os = "macos"))]
-                let info = CollectedInterface::gather(nic, &pci_devices)?;
-                #[cfg(target_os = "macos")]
-                let info = CollectedInterface::gather(nic)?;
-                Ok(info)
-            })();
+                &pci_devices,
+            );
 
             #[cfg(target_os = "linux")]
             if switched_ns {
@@ -104,22 +179,153 @@ fn main() -> Result<()> {
                 }
             }
 
-            (nic, result)
+            match result {
+                Ok(results) => results,
+                Err(e) => {
+                    let label = netns.as_deref().unwrap_or("default");
+                    eprintln!("Error reading namespace {}: {}", label, e.red());
+                    Vec::new()
+                }
+            }
         })
         .collect();
 
-    for (nic, result) in results {
+    // Namespaces come in order (current one first): a stable sort by name
+    // keeps that order among interfaces with the same name.
+    let mut results: Vec<_> = per_ns.into_iter().flatten().collect();
+    results.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut matched = Vec::new();
+    for (name, result) in results {
         match result {
-            Ok(info) => {
-                if matcher.matches(&info) {
-                    info.print(cli.verbose);
-                }
-            }
+            Ok(info) => matched.push(info),
             Err(e) => {
-                eprintln!("Error processing interface {}: {}", nic.name, e.red());
+                eprintln!("Error processing interface {}: {}", name, e.red());
             }
         }
     }
 
+    filter::flag_firmware_mismatch(&mut matched);
+
+    if cli.json {
+        return output::print_json(&matched);
+    }
+
+    if cli.brief {
+        output::print_brief(&matched);
+        return Ok(());
+    }
+
+    if let Some(template) = template {
+        for info in &matched {
+            println!("{}", template.render(info));
+        }
+        return Ok(());
+    }
+
+    for info in &matched {
+        info.print(cli.verbose);
+    }
+
     Ok(())
 }
+
+/// Take a snapshot of the links of the current network namespace, drop the
+/// ones the cheap filters reject and build a `CollectedInterface` for the
+/// rest. `details` also collects the verbose-only ethtool data, `neigh`
+/// the neighbor tables.
+fn collect_namespace(
+    netns: Option<&SmolStr>,
+    matcher: &Matcher,
+    details: bool,
+    nic_stats: Option<&str>,
+    neigh: bool,
+    #[cfg(not(target_os = "macos"))] pci_devices: &OnceLock<
+        HashMap<SmolStr, pci_utils::PciDeviceInfo>,
+    >,
+) -> Result<Vec<(SmolStr, Result<CollectedInterface>)>> {
+    #[cfg(target_os = "linux")]
+    let session = netlink::Session::new()?;
+    #[cfg(target_os = "linux")]
+    let mut links = session.links()?;
+    #[cfg(not(target_os = "linux"))]
+    let links = {
+        let _ = neigh;
+        proc::get_links()?
+    };
+
+    let verdicts: Vec<Prefilter> = links.iter().map(|link| matcher.prefilter(link)).collect();
+    if verdicts.iter().all(|verdict| *verdict == Prefilter::Reject) {
+        return Ok(Vec::new());
+    }
+
+    // The tables below are only worth dumping for the candidates, and only
+    // attached to them. All of them are best effort: a failed dump shows no
+    // entries rather than losing the links.
+    #[cfg(target_os = "linux")]
+    {
+        let indexes: std::collections::HashSet<u32> = links
+            .iter()
+            .zip(&verdicts)
+            .filter(|(_, verdict)| **verdict != Prefilter::Reject)
+            .map(|(link, _)| link.index)
+            .collect();
+        let has_bridges = links.iter().any(|l| {
+            indexes.contains(&l.index)
+                && (l.slave_info.as_ref().is_some_and(|s| s.bridge.is_some())
+                    || l.link_info.as_ref().is_some_and(|i| i.bridge.is_some()))
+        });
+        // Kernels without VLAN filtering just report no table.
+        if details && has_bridges {
+            let _ = session.bridge_vlans(&mut links, &indexes);
+        }
+        if details {
+            let _ = session.qdiscs(&mut links, &indexes);
+            let _ = session.tc_bpf(&mut links, &indexes);
+        }
+        // A failed dump leaves the tables unknown.
+        if neigh {
+            let _ = session.neighbors(&mut links, &indexes);
+        }
+    }
+
+    let candidates: Vec<(&proc::Link, Prefilter)> = links
+        .iter()
+        .zip(verdicts)
+        .filter(|(_, verdict)| *verdict != Prefilter::Reject)
+        .collect();
+
+    #[cfg(target_os = "linux")]
+    let ethtool = if candidates.len() == links.len() {
+        session.ethtool_info(None, details)
+    } else {
+        let names: Vec<SmolStr> = candidates.iter().map(|(l, _)| l.name.clone()).collect();
+        session.ethtool_info(Some(&names), details)
+    };
+    #[cfg(not(target_os = "linux"))]
+    let ethtool = {
+        let _ = details;
+        HashMap::<SmolStr, ifr::EthtoolInfo>::new()
+    };
+
+    #[cfg(not(target_os = "macos"))]
+    let pci_devices = pci_devices.get_or_init(|| pci_utils::get_pci_devices().unwrap_or_default());
+
+    Ok(candidates
+        .into_iter()
+        .filter_map(|(link, verdict)| {
+            let ethtool = ethtool.get(&link.name);
+            #[cfg(not(target_os = "macos"))]
+            let info = CollectedInterface::gather(link, netns, ethtool, nic_stats, pci_devices);
+            #[cfg(target_os = "macos")]
+            let info = CollectedInterface::gather(link, netns, ethtool, nic_stats);
+            // -r is decided on the ethtool link state, which the snapshot
+            // carrier used by the prefilter only approximates.
+            let recheck = verdict == Prefilter::Undecided || matcher.running;
+            match info {
+                Ok(info) if recheck && !matcher.matches(&info) => None,
+                info => Some((link.name.clone(), info)),
+            }
+        })
+        .collect())
+}
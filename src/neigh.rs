@@ -0,0 +1,54 @@
+//! Neighbor (ARP and NDP) entries of an interface, from RTM_GETNEIGH.
+
+use serde::Serialize;
+use smol_str::SmolStr;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Neighbor {
+    pub addr: SmolStr,
+    pub lladdr: Option<SmolStr>,
+    /// REACHABLE, STALE, DELAY, PROBE, FAILED, INCOMPLETE or PERMANENT.
+    pub state: SmolStr,
+    /// The neighbor is an IPv6 router (NTF_ROUTER).
+    pub router: bool,
+}
+
+impl Neighbor {
+    /// Resolution failed or never completed.
+    pub fn is_unresolved(&self) -> bool {
+        self.state == "FAILED" || self.state == "INCOMPLETE"
+    }
+}
+
+/// `2 REACHABLE, 1 STALE`: how many entries are in each state, in order of
+/// first appearance.
+pub fn summary(neighbors: &[Neighbor]) -> String {
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for n in neighbors {
+        match counts.iter_mut().find(|(state, _)| *state == n.state) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((n.state.as_str(), 1)),
+        }
+    }
+    counts
+        .iter()
+        .map(|(state, count)| format!("{} {}", count, state))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Name of the NUD_* state, None for the NOARP and NONE entries that
+/// `ip neigh` does not show either.
+#[cfg(target_os = "linux")]
+pub fn state_name(state: u16) -> Option<&'static str> {
+    match state {
+        0x01 => Some("INCOMPLETE"),
+        0x02 => Some("REACHABLE"),
+        0x04 => Some("STALE"),
+        0x08 => Some("DELAY"),
+        0x10 => Some("PROBE"),
+        0x20 => Some("FAILED"),
+        0x80 => Some("PERMANENT"),
+        _ => None,
+    }
+}
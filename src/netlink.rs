@@ -0,0 +1,2255 @@
+//! Per-namespace netlink session.
+//!
+//! A `Session` owns a single-threaded Tokio runtime, one rtnetlink connection,
+//! one ethtool genetlink connection and a plain NETLINK_ROUTE socket for the tc
+//! dumps, all bound to the network namespace of the thread that created it.
+//! The links of the namespace are taken with one RTM_GETLINK, one RTM_GETADDR
+//! and one IPv4 and one IPv6 RTM_GETROUTE dump; neighbors and bridge VLANs
+//! take one more dump each when asked for. Qdiscs and tc filters are dumped
+//! on the raw socket, and the ethtool data is taken with one dump per message
+//! type, or per device when only some interfaces are shown.
+
+use crate::bpf::{self, BpfProg, TcBpfProg, XdpProg};
+use crate::ethnl::{
+    self, EthnlMessage, ETHTOOL_MSG_LINKINFO_GET, ETHTOOL_MSG_LINKMODES_GET,
+    ETHTOOL_MSG_LINKSTATE_GET,
+};
+use crate::ifr::{Channels, EthtoolInfo, Inet4Addr, LinkModes, LinkState, Rings};
+use crate::linkinfo::{
+    self, BondInfo, BondSlaveInfo, BridgeInfo, BridgePortInfo, BridgeVlan, LinkInfo, SlaveInfo,
+    VethPeer, VlanInfo,
+};
+use crate::neigh::{self, Neighbor};
+use crate::proc::{Inet6Addr, Link, Stats};
+use crate::route::{self, Route};
+use crate::sriov::{self, VfInfo, VfStats};
+use crate::tc::{self, Qdisc};
+use anyhow::Result;
+use ethtool::{
+    EthtoolAttr, EthtoolChannelAttr, EthtoolError, EthtoolFeatureAttr, EthtoolHeader,
+    EthtoolMessage, EthtoolRingAttr,
+};
+use futures::stream::{StreamExt, TryStream, TryStreamExt};
+use netlink_packet_generic::GenlMessage;
+use rtnetlink::packet::constants::{
+    AF_BRIDGE, AF_UNSPEC, NETNSA_NSID_NOT_ASSIGNED, NLM_F_REQUEST, RTEXT_FILTER_BRVLAN_COMPRESSED,
+    RTEXT_FILTER_VF,
+};
+use rtnetlink::packet::nlas::address::Nla as AddrNla;
+use rtnetlink::packet::nlas::link::{
+    AfSpecBridge, Info, InfoBridge, InfoData, InfoIpVlan, InfoMacVlan, InfoMacVtap, InfoVlan,
+    InfoVrf, InfoVxlan, Nla as LinkNla, Prop,
+};
+use rtnetlink::packet::nlas::neighbour::Nla as NeighNla;
+use rtnetlink::packet::nlas::nsid::Nla as NsidNla;
+use rtnetlink::packet::nlas::route::Nla as RouteNla;
+use rtnetlink::packet::nlas::{Nla, NlasIterator};
+use rtnetlink::packet::{
+    AddressMessage, LinkMessage, NeighbourMessage, NetlinkMessage, NetlinkPayload, NsidMessage,
+    RouteMessage, RtnlMessage,
+};
+use rtnetlink::IpVersion;
+use smol_str::SmolStr;
+use std::cell::{Cell, OnceCell};
+use std::collections::{HashMap, HashSet};
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::os::fd::{AsRawFd, OwnedFd, RawFd};
+use tokio::runtime::Runtime;
+
+pub struct Session {
+    rt: Runtime,
+    rtnl: rtnetlink::Handle,
+    ethtool: ethtool::EthtoolHandle,
+    route: OwnedFd,
+    seq: Cell<u32>,
+    /// The RTM_GETQDISC dump, shared by `qdiscs` and `tc_bpf`.
+    qdisc_dump: OnceCell<Vec<(u32, u32, Qdisc)>>,
+}
+
+impl Session {
+    pub fn new() -> Result<Self> {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_io()
+            .build()?;
+
+        let (rtnl, ethtool) = rt.block_on(async {
+            let (connection, rtnl, _) = rtnetlink::new_connection()?;
+            tokio::spawn(connection);
+            let (connection, ethtool, _) = ethtool::new_connection()?;
+            tokio::spawn(connection);
+            Ok::<_, std::io::Error>((rtnl, ethtool))
+        })?;
+
+        let route = nix::sys::socket::socket(
+            nix::sys::socket::AddressFamily::Netlink,
+            nix::sys::socket::SockType::Raw,
+            nix::sys::socket::SockFlag::SOCK_CLOEXEC,
+            nix::sys::socket::SockProtocol::NetlinkRoute,
+        )?;
+
+        Ok(Self {
+            rt,
+            rtnl,
+            ethtool,
+            route,
+            seq: Cell::new(0),
+            qdisc_dump: OnceCell::new(),
+        })
+    }
+
+    /// Dump every link of the namespace together with its addresses and routes.
+    pub fn links(&self) -> Result<Vec<Link>> {
+        let (link_msgs, addr_msgs, route_msgs) = self.rt.block_on(async {
+            // RTEXT_FILTER_VF adds the VF table of SR-IOV physical functions.
+            let links: Vec<LinkMessage> = self
+                .rtnl
+                .link()
+                .get()
+                .set_filter_mask(AF_UNSPEC as u8, RTEXT_FILTER_VF)
+                .execute()
+                .try_collect()
+                .await?;
+            let addrs: Vec<AddressMessage> =
+                self.rtnl.address().get().execute().try_collect().await?;
+            let mut routes: Vec<RouteMessage> = self
+                .rtnl
+                .route()
+                .get(IpVersion::V4)
+                .execute()
+                .try_collect()
+                .await?;
+            let routes6: Vec<RouteMessage> = self
+                .rtnl
+                .route()
+                .get(IpVersion::V6)
+                .execute()
+                .try_collect()
+                .await?;
+            routes.extend(routes6);
+            Ok::<_, rtnetlink::Error>((links, addrs, routes))
+        })?;
+
+        let mut links: Vec<Link> = link_msgs.iter().map(parse_link).collect();
+        let by_index: HashMap<u32, usize> = links
+            .iter()
+            .enumerate()
+            .map(|(pos, link)| (link.index, pos))
+            .collect();
+
+        resolve_links(&mut links, &by_index);
+        self.resolve_veth_peers(&mut links, &by_index);
+
+        for msg in &addr_msgs {
+            if let Some(&pos) = by_index.get(&msg.header.index) {
+                add_address(&mut links[pos], msg);
+            }
+        }
+
+        for msg in &route_msgs {
+            for (oif, route) in parse_route(msg) {
+                if let Some(&pos) = by_index.get(&oif) {
+                    links[pos].routes.push(route);
+                }
+            }
+        }
+
+        links.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(links)
+    }
+
+    /// Find the other end of every veth: by ifindex in this namespace, or with
+    /// a lookup in the peer namespace when the kernel reports IFLA_LINK_NETNSID.
+    fn resolve_veth_peers(&self, links: &mut [Link], by_index: &HashMap<u32, usize>) {
+        let is_veth = |link: &Link| link.link_info.as_ref().is_some_and(|i| i.kind == "veth");
+        let remote = links.iter().any(|l| is_veth(l) && l.link_netnsid.is_some());
+        let netns_names = if remote {
+            self.netns_names()
+        } else {
+            HashMap::new()
+        };
+
+        for pos in 0..links.len() {
+            let link = &links[pos];
+            let Some(index) = link.parent.filter(|_| is_veth(link)) else {
+                continue;
+            };
+            let peer = match link.link_netnsid {
+                None => VethPeer {
+                    name: by_index.get(&index).map(|&p| links[p].name.clone()),
+                    index,
+                    ..Default::default()
+                },
+                Some(nsid) => VethPeer {
+                    name: self.rt.block_on(self.remote_link_name(nsid, index)),
+                    index,
+                    netnsid: Some(nsid),
+                    netns: netns_names.get(&nsid).cloned(),
+                },
+            };
+            if let Some(info) = links[pos].link_info.as_mut() {
+                info.peer = Some(peer);
+            }
+        }
+    }
+
+    /// Map the netnsids this namespace uses for the named namespaces under
+    /// /var/run/netns back to their names.
+    fn netns_names(&self) -> HashMap<i32, SmolStr> {
+        let Ok(entries) = std::fs::read_dir("/var/run/netns") else {
+            return HashMap::new();
+        };
+
+        let mut names = HashMap::new();
+        for entry in entries.flatten() {
+            let Ok(file) = std::fs::File::open(entry.path()) else {
+                continue;
+            };
+            if let Some(id) = self.rt.block_on(self.netnsid_of(file.as_raw_fd())) {
+                let name = SmolStr::from(entry.file_name().to_string_lossy().as_ref());
+                names.insert(id, name);
+            }
+        }
+        names
+    }
+
+    /// RTM_GETNSID for the namespace behind `fd`.
+    async fn netnsid_of(&self, fd: RawFd) -> Option<i32> {
+        let mut msg = NsidMessage::default();
+        msg.nlas.push(NsidNla::Fd(fd as u32));
+        let mut req = NetlinkMessage::from(RtnlMessage::GetNsId(msg));
+        req.header.flags = NLM_F_REQUEST;
+
+        let mut replies = self.rtnl.clone().request(req).ok()?;
+        while let Some(reply) = replies.next().await {
+            if let NetlinkPayload::InnerMessage(RtnlMessage::NewNsId(msg)) = reply.payload {
+                return msg
+                    .nlas
+                    .iter()
+                    .find_map(|nla| match nla {
+                        NsidNla::Id(id) => Some(*id),
+                        _ => None,
+                    })
+                    .filter(|&id| id != NETNSA_NSID_NOT_ASSIGNED);
+            }
+        }
+        None
+    }
+
+    /// Name of link `index` of the namespace known here as `nsid`
+    /// (RTM_GETLINK with IFLA_TARGET_NETNSID).
+    async fn remote_link_name(&self, nsid: i32, index: u32) -> Option<SmolStr> {
+        let mut req = self.rtnl.link().get().match_index(index);
+        req.message_mut()
+            .nlas
+            .push(LinkNla::IfNetnsId(nsid.to_ne_bytes().to_vec()));
+        let msg = req.execute().try_next().await.ok()??;
+        msg.nlas.iter().find_map(|nla| match nla {
+            LinkNla::IfName(name) => Some(SmolStr::from(name.as_str())),
+            _ => None,
+        })
+    }
+
+    /// Fill in the IPv4 and IPv6 neighbor entries of the links in `indexes`.
+    pub fn neighbors(&self, links: &mut [Link], indexes: &HashSet<u32>) -> Result<()> {
+        const NTF_ROUTER: u8 = 0x80;
+
+        let msgs: Vec<NeighbourMessage> = self
+            .rt
+            .block_on(self.rtnl.neighbours().get().execute().try_collect())?;
+
+        let by_index = candidate_positions(links, indexes);
+        for &pos in by_index.values() {
+            links[pos].neighbors = Some(Vec::new());
+        }
+        for msg in &msgs {
+            // An AF_UNSPEC dump also returns the bridge FDB.
+            let family = msg.header.family as i32;
+            if family != libc::AF_INET && family != libc::AF_INET6 {
+                continue;
+            }
+            let Some(state) = neigh::state_name(msg.header.state) else {
+                continue;
+            };
+            let Some(&pos) = by_index.get(&msg.header.ifindex) else {
+                continue;
+            };
+
+            let mut addr = None;
+            let mut lladdr = None;
+            for nla in &msg.nlas {
+                match nla {
+                    NeighNla::Destination(bytes) => addr = ip_str(bytes),
+                    NeighNla::LinkLocalAddress(bytes) if !bytes.is_empty() => {
+                        lladdr = Some(format_hwaddr(bytes))
+                    }
+                    _ => {}
+                }
+            }
+            let Some(addr) = addr else {
+                continue;
+            };
+            links[pos]
+                .neighbors
+                .get_or_insert_with(Vec::new)
+                .push(Neighbor {
+                    addr,
+                    lladdr,
+                    state: SmolStr::new_static(state),
+                    router: msg.header.flags & NTF_ROUTER != 0,
+                });
+        }
+        Ok(())
+    }
+
+    /// Fill in the root, ingress/clsact and per TX queue qdiscs of the links
+    /// in `indexes`.
+    pub fn qdiscs(&self, links: &mut [Link], indexes: &HashSet<u32>) -> Result<()> {
+        let mut qdiscs = self.qdisc_dump()?.to_vec();
+        let by_index = candidate_positions(links, indexes);
+
+        // Roots and ingress first, so that the mq children find their parent.
+        qdiscs.sort_by_key(|(_, parent, _)| *parent != tc::TC_H_ROOT);
+        for (index, parent, mut qdisc) in qdiscs {
+            let Some(&pos) = by_index.get(&index) else {
+                continue;
+            };
+            let link = &mut links[pos];
+            match parent {
+                tc::TC_H_ROOT | tc::TC_H_INGRESS => link.qdiscs.push(qdisc),
+                _ => {
+                    let Some(root) = link.qdiscs.first_mut().filter(|root| {
+                        (root.kind == "mq" || root.kind == "mqprio")
+                            && root.handle == tc::handle_str(parent & 0xffff_0000)
+                    }) else {
+                        continue;
+                    };
+                    qdisc.tx_queue = (parent & 0xffff).checked_sub(1);
+                    root.children.push(qdisc);
+                }
+            }
+        }
+        for link in links.iter_mut() {
+            for root in &mut link.qdiscs {
+                root.children.sort_by_key(|q| q.tx_queue);
+            }
+        }
+        Ok(())
+    }
+
+    /// Fill in the BPF filters of the links in `indexes` that have a clsact
+    /// or ingress qdisc, with one RTM_GETTFILTER dump per direction.
+    pub fn tc_bpf(&self, links: &mut [Link], indexes: &HashSet<u32>) -> Result<()> {
+        // Minors of the clsact filter blocks, under the major of TC_H_INGRESS.
+        const TC_H_MIN_INGRESS: u32 = 0xfff2;
+        const TC_H_MIN_EGRESS: u32 = 0xfff3;
+        const TC_H_MAJ_INGRESS: u32 = tc::TC_H_INGRESS & 0xffff_0000;
+
+        let by_index = candidate_positions(links, indexes);
+        let ingress = self
+            .qdisc_dump()?
+            .iter()
+            .filter(|(_, parent, _)| *parent == tc::TC_H_INGRESS);
+        for (index, _, qdisc) in ingress {
+            let Some(&pos) = by_index.get(index) else {
+                continue;
+            };
+            // The plain ingress qdisc keeps its filters on itself, clsact in
+            // two blocks of its own.
+            let blocks: &[(u32, &'static str)] = if qdisc.kind == "clsact" {
+                &[
+                    (TC_H_MAJ_INGRESS | TC_H_MIN_INGRESS, "ingress"),
+                    (TC_H_MAJ_INGRESS | TC_H_MIN_EGRESS, "egress"),
+                ]
+            } else {
+                &[(TC_H_MAJ_INGRESS, "ingress")]
+            };
+            for &(parent, direction) in blocks {
+                for msg in self.rtnl_dump(RTM_GETTFILTER, &tcmsg(*index, parent))? {
+                    if let Some(prog) = parse_bpf_filter(&msg, direction) {
+                        links[pos].tc_bpf.push(prog);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Every qdisc of the namespace as `(link index, parent, qdisc)`, dumped
+    /// on first use.
+    fn qdisc_dump(&self) -> Result<&[(u32, u32, Qdisc)]> {
+        if self.qdisc_dump.get().is_none() {
+            let qdiscs = self
+                .rtnl_dump(RTM_GETQDISC, &tcmsg(0, 0))?
+                .iter()
+                .filter_map(|msg| parse_qdisc(msg))
+                .collect();
+            let _ = self.qdisc_dump.set(qdiscs);
+        }
+        Ok(self.qdisc_dump.get().map(Vec::as_slice).unwrap_or_default())
+    }
+
+    /// Dump request of type `msg_type` on the plain NETLINK_ROUTE socket,
+    /// returning each reply message whole. The typed rtnetlink decoder expects
+    /// TCA_OPTIONS to be nested attributes and silently drops the qdiscs whose
+    /// options are a struct (pfifo_fast, prio, mqprio, netem...), so the tc
+    /// dumps are decoded here instead.
+    fn rtnl_dump(&self, msg_type: u16, header: &[u8]) -> Result<Vec<Vec<u8>>> {
+        use nix::sys::socket::{recv, sendto, MsgFlags, NetlinkAddr};
+        const NLMSG_HDRLEN: usize = 16;
+        const NLMSG_ERROR: u16 = 2;
+        const NLMSG_DONE: u16 = 3;
+        const NLM_F_DUMP: u16 = 0x300;
+
+        let seq = self.seq.get().wrapping_add(1);
+        self.seq.set(seq);
+
+        let mut req = Vec::with_capacity(NLMSG_HDRLEN + header.len());
+        req.extend_from_slice(&((NLMSG_HDRLEN + header.len()) as u32).to_ne_bytes());
+        req.extend_from_slice(&msg_type.to_ne_bytes());
+        req.extend_from_slice(&(NLM_F_REQUEST | NLM_F_DUMP).to_ne_bytes());
+        req.extend_from_slice(&seq.to_ne_bytes());
+        req.extend_from_slice(&0u32.to_ne_bytes()); // pid
+        req.extend_from_slice(header);
+        let fd = self.route.as_raw_fd();
+        sendto(fd, &req, &NetlinkAddr::new(0, 0), MsgFlags::empty())?;
+
+        let mut msgs = Vec::new();
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            let len = recv(fd, &mut buf, MsgFlags::empty())?;
+            let mut rest = &buf[..len];
+            while rest.len() >= NLMSG_HDRLEN {
+                let msg_len = u32::from_ne_bytes(rest[0..4].try_into()?) as usize;
+                let kind = u16::from_ne_bytes(rest[4..6].try_into()?);
+                let msg_seq = u32::from_ne_bytes(rest[8..12].try_into()?);
+                if msg_len < NLMSG_HDRLEN || msg_len > rest.len() {
+                    anyhow::bail!("truncated netlink message");
+                }
+                // Leftovers of an earlier dump that was given up on.
+                if msg_seq != seq {
+                    rest = &rest[((msg_len + 3) & !3).min(rest.len())..];
+                    continue;
+                }
+                match kind {
+                    NLMSG_DONE => return Ok(msgs),
+                    NLMSG_ERROR => {
+                        let errno = rest
+                            .get(NLMSG_HDRLEN..NLMSG_HDRLEN + 4)
+                            .map_or(0, |b| i32::from_ne_bytes(b.try_into().unwrap()));
+                        if errno != 0 {
+                            return Err(std::io::Error::from_raw_os_error(-errno).into());
+                        }
+                    }
+                    _ => msgs.push(rest[NLMSG_HDRLEN..msg_len].to_vec()),
+                }
+                rest = &rest[((msg_len + 3) & !3).min(rest.len())..];
+            }
+        }
+    }
+
+    /// Fill in the bridge VLAN table of the bridges and bridge ports among
+    /// the links in `indexes`, with one AF_BRIDGE dump.
+    pub fn bridge_vlans(&self, links: &mut [Link], indexes: &HashSet<u32>) -> Result<()> {
+        let msgs: Vec<LinkMessage> = self.rt.block_on(
+            self.rtnl
+                .link()
+                .get()
+                .set_filter_mask(AF_BRIDGE as u8, RTEXT_FILTER_BRVLAN_COMPRESSED)
+                .execute()
+                .try_collect(),
+        )?;
+
+        let by_index = candidate_positions(links, indexes);
+        for msg in &msgs {
+            if let Some(&pos) = by_index.get(&msg.header.index) {
+                links[pos].bridge_vlans = parse_bridge_vlans(msg);
+            }
+        }
+        Ok(())
+    }
+
+    /// Query ethtool port, link modes and link state, and rings, channels and features
+    /// when `details` is set. `devices` restricts the queries to the given
+    /// interfaces; `None` takes one dump of the namespace per message type.
+    /// Devices that do not support a message type are simply left out.
+    pub fn ethtool_info(
+        &self,
+        devices: Option<&[SmolStr]>,
+        details: bool,
+    ) -> HashMap<SmolStr, EthtoolInfo> {
+        let targets: Vec<Option<&str>> = match devices {
+            Some(names) => names.iter().map(|n| Some(n.as_str())).collect(),
+            None => vec![None],
+        };
+        let replies: Vec<EthtoolReplies> = self.rt.block_on(futures::future::join_all(
+            targets
+                .iter()
+                .map(|&target| ethtool_query(&self.ethtool, target, details)),
+        ));
+
+        // The replies of the different message types are joined by device name.
+        let mut info: HashMap<SmolStr, EthtoolInfo> = HashMap::new();
+        let mut ports: HashMap<SmolStr, ethnl::LinkInfoReply> = HashMap::new();
+        for msg in replies.iter().flat_map(|r| &r.link_info) {
+            if let Some(name) = msg.dev_name() {
+                ports.insert(name, ethnl::parse_link_info(msg));
+            }
+        }
+        for msg in replies.iter().flat_map(|r| &r.link_state) {
+            if let Some(name) = msg.dev_name() {
+                let state = link_state(ethnl::parse_link_state(msg));
+                info.entry(name).or_default().link_state = Some(state);
+            }
+        }
+        for msg in replies.iter().flat_map(|r| &r.link_modes) {
+            if let Some(name) = msg.dev_name() {
+                let port = ports.remove(&name).unwrap_or_default();
+                let entry = info.entry(name).or_default();
+                let modes = ethnl::parse_link_modes(msg);
+                (entry.media, entry.speed) = media(&port, &modes);
+                entry.link_modes = Some(link_modes(&port, modes));
+            }
+        }
+        for msg in replies.iter().flat_map(|r| &r.other) {
+            if let Some((name, r)) = parse_rings(msg) {
+                info.entry(name).or_default().rings = Some(r);
+            } else if let Some((name, c)) = parse_channels(msg) {
+                info.entry(name).or_default().channels = Some(c);
+            } else if let Some((name, f)) = parse_features(msg) {
+                info.entry(name).or_default().features = Some(f);
+            }
+        }
+        info
+    }
+}
+
+#[derive(Default)]
+struct EthtoolReplies {
+    link_info: Vec<EthnlMessage>,
+    link_modes: Vec<EthnlMessage>,
+    link_state: Vec<EthnlMessage>,
+    other: Vec<EthtoolMessage>,
+}
+
+/// Run the ethtool requests for one device (or one dump per message type, if
+/// `target` is `None`) concurrently and return all the replies.
+async fn ethtool_query(
+    handle: &ethtool::EthtoolHandle,
+    target: Option<&str>,
+    details: bool,
+) -> EthtoolReplies {
+    let request = |cmd: u8| {
+        let mut handle = handle.clone();
+        async move {
+            ethnl::request(&mut handle, EthnlMessage::get(cmd, target))
+                .await
+                .unwrap_or_default()
+        }
+    };
+    let link_info = request(ETHTOOL_MSG_LINKINFO_GET);
+    let link_modes = request(ETHTOOL_MSG_LINKMODES_GET);
+    let link_state = request(ETHTOOL_MSG_LINKSTATE_GET);
+
+    if !details {
+        let (link_info, link_modes, link_state) = futures::join!(link_info, link_modes, link_state);
+        return EthtoolReplies {
+            link_info,
+            link_modes,
+            link_state,
+            other: Vec::new(),
+        };
+    }
+
+    let mut handle = handle.clone();
+    let rings = handle.ring().get(target).execute();
+    let channels = handle.channel().get(target).execute();
+    let features = handle.feature().get(target).execute();
+    let (link_info, link_modes, link_state, mut other, channels, features) = futures::join!(
+        link_info,
+        link_modes,
+        link_state,
+        async { collect_reply(rings.await).await },
+        async { collect_reply(channels.await).await },
+        async { collect_reply(features.await).await },
+    );
+    other.extend(channels);
+    other.extend(features);
+    EthtoolReplies {
+        link_info,
+        link_modes,
+        link_state,
+        other,
+    }
+}
+
+async fn collect_reply(
+    stream: impl TryStream<Ok = GenlMessage<EthtoolMessage>, Error = EthtoolError>,
+) -> Vec<EthtoolMessage> {
+    let mut msgs = Vec::new();
+    let stream = stream.into_stream();
+    futures::pin_mut!(stream);
+    while let Ok(Some(msg)) = stream.try_next().await {
+        msgs.push(msg.payload);
+    }
+    msgs
+}
+
+// Values of ETHTOOL_A_LINKINFO_PORT, ETHTOOL_A_LINKINFO_TRANSCEIVER and
+// ETHTOOL_A_LINKMODES_DUPLEX (see linux/ethtool.h).
+fn port_name(port: u8) -> &'static str {
+    match port {
+        0x00 => "TP",
+        0x01 => "AUI",
+        0x02 => "BNC",
+        0x03 => "MII",
+        0x04 => "FIBRE",
+        0x05 => "DA",
+        0xef => "None",
+        _ => "Other",
+    }
+}
+
+fn transceiver_name(xcvr: u8) -> &'static str {
+    match xcvr {
+        0x00 => "internal",
+        0x01 => "external",
+        _ => "unknown",
+    }
+}
+
+fn duplex_name(duplex: Option<u8>) -> &'static str {
+    match duplex {
+        Some(0x00) => "half",
+        Some(0x01) => "full",
+        _ => "unknown",
+    }
+}
+
+/// Media description (e.g. "FIBRE 100000Mb/s full") and speed in Mb/s.
+fn media(
+    port: &ethnl::LinkInfoReply,
+    modes: &ethnl::LinkModesReply,
+) -> (Option<SmolStr>, Option<u32>) {
+    let port = port.port.map(port_name);
+    let speed = modes
+        .speed
+        .filter(|&s| s != 0 && s != 0xFFFF && s != 0xFFFFFFFF);
+
+    let media = match (port, speed) {
+        (Some(port), Some(speed)) => {
+            format!("{} {}Mb/s {}", port, speed, duplex_name(modes.duplex))
+        }
+        (None, Some(speed)) => format!("{}Mb/s {}", speed, duplex_name(modes.duplex)),
+        (Some(port), None) => format!("{} (unknown speed)", port),
+        (None, None) => "unknown speed".to_string(),
+    };
+    (Some(SmolStr::from(media)), speed)
+}
+
+fn link_state(state: ethnl::LinkStateReply) -> LinkState {
+    let ext_substate = match (state.ext_state, state.ext_substate) {
+        (Some(ext), Some(sub)) => Some(SmolStr::new_static(ethnl::ext_substate_name(ext, sub))),
+        _ => None,
+    };
+    LinkState {
+        link: state.link,
+        ext_state: state
+            .ext_state
+            .map(|s| SmolStr::new_static(ethnl::ext_state_name(s))),
+        ext_substate,
+        sqi: state.sqi,
+        sqi_max: state.sqi_max,
+        down_count: state.down_count,
+    }
+}
+
+fn link_modes(port: &ethnl::LinkInfoReply, modes: ethnl::LinkModesReply) -> LinkModes {
+    // The bitsets also carry port, pause and FEC bits: keep the speed modes.
+    let speed_modes = |names: Vec<SmolStr>| -> Vec<SmolStr> {
+        names.into_iter().filter(|n| n.contains("base")).collect()
+    };
+    let supported = speed_modes(modes.supported);
+    let advertised = speed_modes(modes.advertised);
+    let partner = speed_modes(modes.partner);
+
+    let mismatch = modes.autoneg == Some(true)
+        && !advertised.is_empty()
+        && !partner.is_empty()
+        && !advertised.iter().any(|m| partner.contains(m));
+
+    LinkModes {
+        port: port.port.map(|p| SmolStr::new_static(port_name(p))),
+        transceiver: port
+            .transceiver
+            .map(|t| SmolStr::new_static(transceiver_name(t))),
+        autoneg: modes.autoneg,
+        lanes: modes.lanes,
+        supported,
+        advertised,
+        partner,
+        mismatch,
+    }
+}
+
+fn header_name(header: &[EthtoolHeader]) -> Option<SmolStr> {
+    header.iter().find_map(|h| match h {
+        EthtoolHeader::DevName(name) => Some(SmolStr::from(name.as_str())),
+        _ => None,
+    })
+}
+
+fn parse_rings(msg: &EthtoolMessage) -> Option<(SmolStr, Rings)> {
+    let mut name = None;
+    let mut rings = Rings::default();
+
+    for nla in &msg.nlas {
+        if let EthtoolAttr::Ring(attr) = nla {
+            match attr {
+                EthtoolRingAttr::Header(h) => name = header_name(h),
+                EthtoolRingAttr::Rx(val) => rings.rx = *val,
+                EthtoolRingAttr::Tx(val) => rings.tx = *val,
+                _ => {}
+            }
+        }
+    }
+
+    Some((name?, rings))
+}
+
+fn parse_channels(msg: &EthtoolMessage) -> Option<(SmolStr, Channels)> {
+    let mut name = None;
+    let mut channels = Channels::default();
+
+    for nla in &msg.nlas {
+        if let EthtoolAttr::Channel(attr) = nla {
+            match attr {
+                EthtoolChannelAttr::Header(h) => name = header_name(h),
+                EthtoolChannelAttr::RxCount(val) => channels.rx = *val,
+                EthtoolChannelAttr::TxCount(val) => channels.tx = *val,
+                EthtoolChannelAttr::OtherCount(val) => channels.other = *val,
+                EthtoolChannelAttr::CombinedCount(val) => channels.combined = *val,
+                _ => {}
+            }
+        }
+    }
+
+    Some((name?, channels))
+}
+
+fn parse_features(msg: &EthtoolMessage) -> Option<(SmolStr, Vec<SmolStr>)> {
+    let mut name = None;
+    let mut features = Vec::new();
+
+    for nla in &msg.nlas {
+        match nla {
+            EthtoolAttr::Feature(EthtoolFeatureAttr::Header(h)) => name = header_name(h),
+            EthtoolAttr::Feature(EthtoolFeatureAttr::Active(bits)) => {
+                for bit in bits {
+                    if bit.value {
+                        let feature_name = match bit.name.as_str() {
+                            "tx-tcp-segmentation" => "tso",
+                            "tx-generic-segmentation" => "gso",
+                            "rx-gro" => "gro",
+                            "rx-lro" => "lro",
+                            "rx-checksum" => "rx-csum",
+                            "tx-checksum-ip-generic" => "tx-csum",
+                            "tx-checksum-ipv4" => "tx-csum-ipv4",
+                            "tx-checksum-ipv6" => "tx-csum-ipv6",
+                            "tx-scatter-gather" => "sg",
+                            "tx-scatter-gather-fraglist" => "sg-frag",
+                            "tx-vlan-hw-insert" => "tx-vlan",
+                            "rx-vlan-hw-parse" => "rx-vlan",
+                            "highdma" => "highdma",
+                            "rx-hashing" => "rxhash",
+                            "rx-ntuple-filter" => "ntuple",
+                            other => other,
+                        };
+                        features.push(SmolStr::from(feature_name));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Some((name?, features))
+}
+
+fn format_hwaddr(bytes: &[u8]) -> SmolStr {
+    let parts: Vec<String> = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    SmolStr::from(parts.join(":"))
+}
+
+fn parse_link(msg: &LinkMessage) -> Link {
+    let mut link = Link {
+        index: msg.header.index,
+        flags: msg.header.flags,
+        ..Default::default()
+    };
+
+    for nla in &msg.nlas {
+        match nla {
+            LinkNla::IfName(name) => link.name = SmolStr::from(name.as_str()),
+            LinkNla::Address(addr) if !addr.is_empty() => link.mac = Some(format_hwaddr(addr)),
+            LinkNla::Mtu(mtu) => link.mtu = Some(*mtu),
+            LinkNla::Carrier(carrier) => link.carrier = Some(*carrier != 0),
+            LinkNla::Info(infos) => {
+                link.link_info = parse_link_info(infos);
+                link.slave_info = parse_slave_info(infos);
+            }
+            LinkNla::Master(index) => link.master = Some(*index),
+            LinkNla::Link(index) => link.parent = Some(*index),
+            LinkNla::NetnsId(id) => link.link_netnsid = Some(*id),
+            LinkNla::Stats64(bytes) => link.stats = parse_stats64(bytes),
+            LinkNla::Xdp(raw) => link.xdp = parse_xdp(raw),
+            LinkNla::VfInfoList(raw) => link.vfs = parse_vfinfo_list(raw),
+            LinkNla::PropList(props) => {
+                for prop in props {
+                    if let Prop::AltIfName(altname) = prop {
+                        if !altname.is_empty() {
+                            link.altnames.push(SmolStr::from(altname.as_str()));
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    link
+}
+
+fn parse_link_info(infos: &[Info]) -> Option<LinkInfo> {
+    let mut info = infos.iter().find_map(|i| match i {
+        Info::Kind(kind) => {
+            let mut name = vec![0u8; kind.value_len()];
+            kind.emit_value(&mut name);
+            let len = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+            Some(LinkInfo::new(&String::from_utf8_lossy(&name[..len])))
+        }
+        _ => None,
+    })?;
+
+    for i in infos {
+        let Info::Data(data) = i else {
+            continue;
+        };
+        match data {
+            InfoData::Vlan(attrs) => info.vlan = Some(parse_vlan(attrs)),
+            InfoData::Bond(raw) => info.bond = Some(parse_bond(raw)),
+            InfoData::Bridge(attrs) => info.bridge = Some(parse_bridge(attrs)),
+            InfoData::MacVlan(attrs) => {
+                for attr in attrs {
+                    if let InfoMacVlan::Mode(mode) = attr {
+                        info.set("mode", linkinfo::macvlan_mode_name(*mode));
+                    }
+                }
+            }
+            InfoData::MacVtap(attrs) => {
+                for attr in attrs {
+                    if let InfoMacVtap::Mode(mode) = attr {
+                        info.set("mode", linkinfo::macvlan_mode_name(*mode));
+                    }
+                }
+            }
+            InfoData::IpVlan(attrs) => {
+                for attr in attrs {
+                    if let InfoIpVlan::Mode(mode) = attr {
+                        info.set("mode", linkinfo::ipvlan_mode_name(*mode));
+                    }
+                }
+            }
+            InfoData::Vxlan(attrs) => {
+                for attr in attrs {
+                    if let InfoVxlan::Id(id) = attr {
+                        info.set("id", id);
+                    }
+                }
+            }
+            InfoData::Vrf(attrs) => {
+                for attr in attrs {
+                    if let InfoVrf::TableId(table) = attr {
+                        info.set("table", table);
+                    }
+                }
+            }
+            InfoData::Tun(raw) => {
+                const IFLA_TUN_TYPE: u16 = 3;
+                // IFF_TUN (1) or IFF_TAP (2)
+                for nla in NlasIterator::new(raw.as_slice()).flatten() {
+                    if nla.kind() == IFLA_TUN_TYPE {
+                        match nla.value().first() {
+                            Some(1) => info.set("type", "tun"),
+                            Some(2) => info.set("type", "tap"),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Some(info)
+}
+
+fn parse_vlan(attrs: &[InfoVlan]) -> VlanInfo {
+    let mut vlan = VlanInfo::default();
+    for attr in attrs {
+        match attr {
+            InfoVlan::Id(id) => vlan.id = *id,
+            InfoVlan::Protocol(proto) => vlan.protocol = linkinfo::vlan_protocol_name(*proto),
+            InfoVlan::Flags((flags, _mask)) => vlan.flags = linkinfo::vlan_flag_names(*flags),
+            InfoVlan::IngressQos(raw) => vlan.ingress_qos = parse_qos_map(raw),
+            InfoVlan::EgressQos(raw) => vlan.egress_qos = parse_qos_map(raw),
+            _ => {}
+        }
+    }
+    vlan
+}
+
+/// A list of IFLA_VLAN_QOS_MAPPING attributes, each a `struct ifla_vlan_qos_mapping`.
+fn parse_qos_map(raw: &[u8]) -> Vec<SmolStr> {
+    NlasIterator::new(raw)
+        .flatten()
+        .filter_map(|nla| {
+            let value = nla.value();
+            let from = u32::from_ne_bytes(value.get(0..4)?.try_into().ok()?);
+            let to = u32::from_ne_bytes(value.get(4..8)?.try_into().ok()?);
+            Some(smol_str::format_smolstr!("{}:{}", from, to))
+        })
+        .collect()
+}
+
+fn parse_slave_info(infos: &[Info]) -> Option<SlaveInfo> {
+    let mut info = infos.iter().find_map(|i| match i {
+        Info::SlaveKind(kind) => {
+            let len = kind.iter().position(|&b| b == 0).unwrap_or(kind.len());
+            Some(SlaveInfo {
+                kind: SmolStr::from(String::from_utf8_lossy(&kind[..len])),
+                ..Default::default()
+            })
+        }
+        _ => None,
+    })?;
+
+    for i in infos {
+        if let Info::SlaveData(raw) = i {
+            match info.kind.as_str() {
+                "bond" => info.bond = Some(parse_bond_slave(raw)),
+                "bridge" => info.bridge = Some(parse_bridge_port(raw)),
+                _ => {}
+            }
+        }
+    }
+
+    Some(info)
+}
+
+fn nla_u8(value: &[u8]) -> Option<u8> {
+    value.first().copied()
+}
+
+fn nla_u16(value: &[u8]) -> Option<u16> {
+    Some(u16::from_ne_bytes(value.get(0..2)?.try_into().ok()?))
+}
+
+fn nla_u32(value: &[u8]) -> Option<u32> {
+    Some(u32::from_ne_bytes(value.get(0..4)?.try_into().ok()?))
+}
+
+fn nla_u64(value: &[u8]) -> Option<u64> {
+    Some(u64::from_ne_bytes(value.get(0..8)?.try_into().ok()?))
+}
+
+/// IFLA_BOND_* attributes of a bonding master.
+fn parse_bond(raw: &[u8]) -> BondInfo {
+    const IFLA_BOND_MODE: u16 = 1;
+    const IFLA_BOND_ACTIVE_SLAVE: u16 = 2;
+    const IFLA_BOND_MIIMON: u16 = 3;
+    const IFLA_BOND_UPDELAY: u16 = 4;
+    const IFLA_BOND_DOWNDELAY: u16 = 5;
+    const IFLA_BOND_XMIT_HASH_POLICY: u16 = 14;
+    const IFLA_BOND_AD_LACP_RATE: u16 = 21;
+    const IFLA_BOND_AD_INFO: u16 = 23;
+    const IFLA_BOND_AD_INFO_AGGREGATOR: u16 = 1;
+    const BOND_MODE_8023AD: u8 = 4;
+
+    let mut bond = BondInfo::default();
+    let mut mode = None;
+    let mut lacp_rate = None;
+    for nla in NlasIterator::new(raw).flatten() {
+        let value = nla.value();
+        match nla.kind() {
+            IFLA_BOND_MODE => mode = nla_u8(value),
+            IFLA_BOND_ACTIVE_SLAVE => bond.active_slave_index = nla_u32(value),
+            IFLA_BOND_MIIMON => bond.miimon = nla_u32(value),
+            IFLA_BOND_UPDELAY => bond.updelay = nla_u32(value),
+            IFLA_BOND_DOWNDELAY => bond.downdelay = nla_u32(value),
+            IFLA_BOND_XMIT_HASH_POLICY => {
+                bond.xmit_hash_policy = nla_u8(value).map(linkinfo::bond_xmit_hash_policy_name)
+            }
+            IFLA_BOND_AD_LACP_RATE => lacp_rate = nla_u8(value),
+            IFLA_BOND_AD_INFO => {
+                bond.aggregator = NlasIterator::new(value)
+                    .flatten()
+                    .find(|n| n.kind() == IFLA_BOND_AD_INFO_AGGREGATOR)
+                    .and_then(|n| nla_u16(n.value()));
+            }
+            _ => {}
+        }
+    }
+
+    // The kernel reports the LACP rate in every mode, but it only means
+    // something for 802.3ad.
+    if mode == Some(BOND_MODE_8023AD) {
+        bond.lacp_rate =
+            lacp_rate.map(|rate| SmolStr::new_static(if rate == 1 { "fast" } else { "slow" }));
+    }
+    bond.mode = mode.map(linkinfo::bond_mode_name).unwrap_or_default();
+    bond
+}
+
+/// IFLA_BOND_SLAVE_* attributes of a bond leg.
+fn parse_bond_slave(raw: &[u8]) -> BondSlaveInfo {
+    const IFLA_BOND_SLAVE_STATE: u16 = 1;
+    const IFLA_BOND_SLAVE_MII_STATUS: u16 = 2;
+    const IFLA_BOND_SLAVE_LINK_FAILURE_COUNT: u16 = 3;
+    const IFLA_BOND_SLAVE_PERM_HWADDR: u16 = 4;
+    const IFLA_BOND_SLAVE_AD_AGGREGATOR_ID: u16 = 6;
+    const IFLA_BOND_SLAVE_AD_ACTOR_OPER_PORT_STATE: u16 = 7;
+    const IFLA_BOND_SLAVE_AD_PARTNER_OPER_PORT_STATE: u16 = 8;
+
+    let mut slave = BondSlaveInfo::default();
+    for nla in NlasIterator::new(raw).flatten() {
+        let value = nla.value();
+        match nla.kind() {
+            IFLA_BOND_SLAVE_STATE => {
+                slave.state = nla_u8(value)
+                    .map(linkinfo::bond_slave_state_name)
+                    .unwrap_or_default()
+            }
+            IFLA_BOND_SLAVE_MII_STATUS => {
+                slave.mii_status = nla_u8(value)
+                    .map(linkinfo::bond_mii_status_name)
+                    .unwrap_or_default()
+            }
+            IFLA_BOND_SLAVE_LINK_FAILURE_COUNT => {
+                slave.link_failure_count = nla_u32(value).unwrap_or(0)
+            }
+            IFLA_BOND_SLAVE_PERM_HWADDR if !value.is_empty() => {
+                slave.perm_hwaddr = Some(format_hwaddr(value))
+            }
+            IFLA_BOND_SLAVE_AD_AGGREGATOR_ID => slave.aggregator = nla_u16(value),
+            IFLA_BOND_SLAVE_AD_ACTOR_OPER_PORT_STATE => {
+                slave.actor_state = nla_u8(value).map(linkinfo::lacp_state_names)
+            }
+            IFLA_BOND_SLAVE_AD_PARTNER_OPER_PORT_STATE => {
+                // a u16 on the wire, of which only the low byte is used
+                slave.partner_state = nla_u16(value).map(|s| linkinfo::lacp_state_names(s as u8))
+            }
+            _ => {}
+        }
+    }
+    slave
+}
+
+fn parse_bridge(attrs: &[InfoBridge]) -> BridgeInfo {
+    // struct ifla_bridge_id: the priority bytes are in network order.
+    let bridge_id = |(priority, mac): &(u16, [u8; 6])| {
+        let p = priority.to_ne_bytes();
+        smol_str::format_smolstr!("{:02x}{:02x}.{}", p[0], p[1], format_hwaddr(mac))
+    };
+    // Timers are in USER_HZ (centiseconds).
+    let seconds = |t: u32| t / 100;
+
+    let mut bridge = BridgeInfo::default();
+    for attr in attrs {
+        match attr {
+            InfoBridge::BridgeId(id) => bridge.bridge_id = Some(bridge_id(id)),
+            InfoBridge::RootId(id) => bridge.root_id = Some(bridge_id(id)),
+            InfoBridge::RootPort(port) => bridge.root_port = Some(*port),
+            InfoBridge::Priority(priority) => bridge.priority = Some(*priority),
+            InfoBridge::StpState(state) => bridge.stp_state = linkinfo::stp_state_name(*state),
+            InfoBridge::ForwardDelay(t) => bridge.forward_delay = Some(seconds(*t)),
+            InfoBridge::AgeingTime(t) => bridge.ageing_time = Some(seconds(*t)),
+            InfoBridge::VlanFiltering(on) => bridge.vlan_filtering = *on != 0,
+            _ => {}
+        }
+    }
+    bridge
+}
+
+/// IFLA_BRPORT_* attributes of a bridge port.
+fn parse_bridge_port(raw: &[u8]) -> BridgePortInfo {
+    const IFLA_BRPORT_STATE: u16 = 1;
+    const IFLA_BRPORT_PRIORITY: u16 = 2;
+    const IFLA_BRPORT_COST: u16 = 3;
+    const IFLA_BRPORT_MODE: u16 = 4; // hairpin
+    const IFLA_BRPORT_LEARNING: u16 = 8;
+    const IFLA_BRPORT_UNICAST_FLOOD: u16 = 9;
+    const IFLA_BRPORT_MCAST_FLOOD: u16 = 27;
+    const IFLA_BRPORT_BCAST_FLOOD: u16 = 30;
+    const IFLA_BRPORT_NEIGH_SUPPRESS: u16 = 32;
+
+    let mut port = BridgePortInfo::default();
+    for nla in NlasIterator::new(raw).flatten() {
+        let value = nla.value();
+        let on = nla_u8(value).is_some_and(|v| v != 0);
+        match nla.kind() {
+            IFLA_BRPORT_STATE => {
+                port.state = nla_u8(value)
+                    .map(linkinfo::bridge_port_state_name)
+                    .unwrap_or_default()
+            }
+            IFLA_BRPORT_PRIORITY => port.priority = nla_u16(value),
+            IFLA_BRPORT_COST => port.cost = nla_u32(value),
+            IFLA_BRPORT_MODE => port.hairpin = on,
+            IFLA_BRPORT_LEARNING => port.learning = on,
+            IFLA_BRPORT_UNICAST_FLOOD => port.flood = on,
+            IFLA_BRPORT_MCAST_FLOOD => port.mcast_flood = on,
+            IFLA_BRPORT_BCAST_FLOOD => port.bcast_flood = on,
+            IFLA_BRPORT_NEIGH_SUPPRESS => port.neigh_suppress = on,
+            _ => {}
+        }
+    }
+    port
+}
+
+/// IFLA_BRIDGE_VLAN_INFO entries of an AF_BRIDGE link message.
+fn parse_bridge_vlans(msg: &LinkMessage) -> Vec<BridgeVlan> {
+    // BRIDGE_VLAN_INFO_* from linux/if_bridge.h
+    const PVID: u16 = 1 << 1;
+    const UNTAGGED: u16 = 1 << 2;
+    const RANGE_BEGIN: u16 = 1 << 3;
+    const RANGE_END: u16 = 1 << 4;
+
+    let mut table = Vec::new();
+    let mut range_begin = None;
+    for nla in &msg.nlas {
+        let LinkNla::AfSpecBridge(attrs) = nla else {
+            continue;
+        };
+        for attr in attrs {
+            let AfSpecBridge::VlanInfo(info) = attr else {
+                continue;
+            };
+            if info.flags & RANGE_BEGIN != 0 {
+                range_begin = Some(info.vid);
+                continue;
+            }
+            let vid = if info.flags & RANGE_END != 0 {
+                range_begin.take().unwrap_or(info.vid)
+            } else {
+                info.vid
+            };
+            linkinfo::push_bridge_vlan(
+                &mut table,
+                BridgeVlan {
+                    vid,
+                    vid_end: info.vid,
+                    pvid: info.flags & PVID != 0,
+                    untagged: info.flags & UNTAGGED != 0,
+                },
+            );
+        }
+    }
+    table
+}
+
+/// Position in `links` of each link in `indexes`, by ifindex.
+fn candidate_positions(links: &[Link], indexes: &HashSet<u32>) -> HashMap<u32, usize> {
+    links
+        .iter()
+        .enumerate()
+        .filter(|(_, link)| indexes.contains(&link.index))
+        .map(|(pos, link)| (link.index, pos))
+        .collect()
+}
+
+/// Turn the ifindexes that links refer to into names: the lower device of
+/// VLANs, the master of enslaved links and the active slave of bonds. Parents
+/// and masters also get the list of their VLANs and slaves.
+fn resolve_links(links: &mut [Link], by_index: &HashMap<u32, usize>) {
+    let name_of = |links: &[Link], index: Option<u32>| {
+        index
+            .and_then(|index| by_index.get(&index))
+            .map(|&pos| links[pos].name.clone())
+    };
+
+    for pos in 0..links.len() {
+        let link = &links[pos];
+        let is_vlan = link.link_info.as_ref().is_some_and(|l| l.vlan.is_some());
+        if is_vlan && link.link_netnsid.is_none() {
+            if let Some(&parent) = link.parent.and_then(|index| by_index.get(&index)) {
+                let name = link.name.clone();
+                let parent_name = links[parent].name.clone();
+                links[parent].vlans.push(name);
+                if let Some(vlan) = links[pos].link_info.as_mut().and_then(|l| l.vlan.as_mut()) {
+                    vlan.parent = Some(parent_name);
+                }
+            }
+        }
+
+        let link = &links[pos];
+        if let Some(&master) = link.master.and_then(|index| by_index.get(&index)) {
+            let name = link.name.clone();
+            let master_name = links[master].name.clone();
+            links[master].slaves.push(name);
+            if let Some(slave) = links[pos].slave_info.as_mut() {
+                slave.master = Some(master_name);
+            }
+        }
+
+        let active = links[pos]
+            .link_info
+            .as_ref()
+            .and_then(|l| l.bond.as_ref())
+            .and_then(|b| b.active_slave_index);
+        let active = name_of(links, active);
+        if let Some(bond) = links[pos].link_info.as_mut().and_then(|l| l.bond.as_mut()) {
+            bond.active_slave = active;
+        }
+    }
+}
+
+/// Fold a `struct rtnl_link_stats64` into the /proc/net/dev columns the same
+/// way the kernel does, keeping the counters that /proc/net/dev does not show.
+fn parse_stats64(bytes: &[u8]) -> Option<Stats> {
+    let field = |idx: usize| -> Option<u64> {
+        let raw = bytes.get(idx * 8..idx * 8 + 8)?;
+        Some(u64::from_ne_bytes(raw.try_into().ok()?))
+    };
+    let c = |idx: usize| field(idx).unwrap_or(0);
+
+    if bytes.len() < 23 * 8 {
+        return None;
+    }
+
+    Some(Stats {
+        rx_bytes: c(2),
+        rx_packets: c(0),
+        rx_errors: c(4),
+        rx_dropped: c(6) + c(15),
+        rx_fifo: c(14),
+        rx_frame: c(10) + c(11) + c(12) + c(13),
+        rx_compressed: c(21),
+        rx_multicast: c(8),
+        tx_bytes: c(3),
+        tx_packets: c(1),
+        tx_errors: c(5),
+        tx_dropped: c(7),
+        tx_fifo: c(18),
+        tx_collisions: c(9),
+        tx_carrier: c(16) + c(17) + c(19) + c(20),
+        tx_compressed: c(22),
+        rx_missed: field(15),
+        rx_nohandler: field(23),
+        rx_otherhost_dropped: field(24),
+    })
+}
+
+fn add_address(link: &mut Link, msg: &AddressMessage) {
+    const IFA_PROTO: u16 = 11; // not known to netlink-packet-route yet
+
+    let mut address = None;
+    let mut local = None;
+    // IFA_FLAGS carries the full 32 bits, the header only the low 8.
+    let mut flags = msg.header.flags as u32;
+    let mut lifetimes = (None, None);
+    let mut label = None;
+    let mut broadcast = None;
+    let mut proto = None;
+    for nla in &msg.nlas {
+        match nla {
+            AddrNla::Address(bytes) => address = Some(bytes.as_slice()),
+            AddrNla::Local(bytes) => local = Some(bytes.as_slice()),
+            AddrNla::Label(l) if *l != link.name => label = Some(SmolStr::from(l.as_str())),
+            AddrNla::Broadcast(bytes) => broadcast = ipv4_str(bytes),
+            AddrNla::Other(nla) if nla.kind() == IFA_PROTO && nla.value_len() == 1 => {
+                let mut value = [0u8; 1];
+                nla.emit_value(&mut value);
+                proto = Some(addr_proto_name(value[0]));
+            }
+            AddrNla::Flags(f) => flags = *f,
+            AddrNla::CacheInfo(bytes) => lifetimes = parse_cacheinfo(bytes),
+            _ => {}
+        }
+    }
+
+    let prefix = msg.header.prefix_len;
+    match msg.header.family as i32 {
+        libc::AF_INET => {
+            // IFA_LOCAL is the interface address, IFA_ADDRESS is the peer on
+            // point-to-point links (and the same as IFA_LOCAL otherwise).
+            let Some(addr) = local.or(address).and_then(ipv4_str) else {
+                return;
+            };
+            let peer = match (local, address) {
+                (Some(local), Some(address)) if local != address => ipv4_str(address),
+                _ => None,
+            };
+            let mask = u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0);
+            link.ipv4.push(Inet4Addr {
+                addr,
+                mask: Some(SmolStr::from(Ipv4Addr::from(mask).to_string())),
+                prefix: Some(prefix as u32),
+                label,
+                broadcast,
+                peer,
+                flags: addr_flag_names(flags, libc::AF_INET),
+                valid_lft: lifetimes.1,
+                preferred_lft: lifetimes.0,
+                proto,
+            });
+        }
+        libc::AF_INET6 => {
+            let Some(bytes) = address.or(local) else {
+                return;
+            };
+            let Ok(octets) = <[u8; 16]>::try_from(bytes) else {
+                return;
+            };
+            link.ipv6.push(Inet6Addr {
+                addr: SmolStr::from(Ipv6Addr::from(octets).to_string()),
+                prefix: prefix as u32,
+                scope: SmolStr::new_static(scope_name(msg.header.scope)),
+                flags: addr_flag_names(flags, libc::AF_INET6),
+                valid_lft: lifetimes.1,
+                preferred_lft: lifetimes.0,
+            });
+        }
+        _ => {}
+    }
+}
+
+fn ipv4_str(bytes: &[u8]) -> Option<SmolStr> {
+    let octets = <[u8; 4]>::try_from(bytes).ok()?;
+    Some(SmolStr::from(Ipv4Addr::from(octets).to_string()))
+}
+
+/// An IPv4 or IPv6 address, told apart by length.
+fn ip_str(bytes: &[u8]) -> Option<SmolStr> {
+    match bytes.len() {
+        4 => ipv4_str(bytes),
+        16 => {
+            let octets = <[u8; 16]>::try_from(bytes).ok()?;
+            Some(SmolStr::from(Ipv6Addr::from(octets).to_string()))
+        }
+        _ => None,
+    }
+}
+
+/// IFA_PROTO: the kernel tags the addresses it creates itself, and
+/// userspace may set any other value.
+fn addr_proto_name(proto: u8) -> SmolStr {
+    match proto {
+        0 => SmolStr::new_static("unspec"),
+        1 => SmolStr::new_static("kernel_lo"),
+        2 => SmolStr::new_static("kernel_ll"),
+        3 => SmolStr::new_static("kernel_ra"),
+        _ => smol_str::format_smolstr!("{}", proto),
+    }
+}
+
+/// The routes of `msg` worth showing on an interface, with the ifindex they
+/// leave through: one per next hop for multipath routes.
+fn parse_route(msg: &RouteMessage) -> Vec<(u32, Route)> {
+    const RTN_UNICAST: u8 = 1;
+    const RTPROT_KERNEL: u8 = 2;
+    const RTPROT_RA: u8 = 9;
+    const RTPROT_DHCP: u8 = 16;
+    const RTA_GATEWAY: u16 = 5;
+
+    let header = &msg.header;
+    let is_default = header.destination_prefix_length == 0;
+    let learned = matches!(header.protocol, RTPROT_KERNEL | RTPROT_RA | RTPROT_DHCP);
+    if header.kind != RTN_UNICAST || !(is_default || learned) {
+        return Vec::new();
+    }
+
+    let mut table = header.table as u32;
+    let mut destination = None;
+    let mut gateway = None;
+    let mut metric = None;
+    let mut oif = None;
+    let mut multipath = None;
+    for nla in &msg.nlas {
+        match nla {
+            RouteNla::Table(t) => table = *t,
+            RouteNla::Destination(bytes) => destination = ip_str(bytes),
+            RouteNla::Gateway(bytes) => gateway = ip_str(bytes),
+            RouteNla::Priority(p) => metric = Some(*p),
+            RouteNla::Oif(index) => oif = Some(*index),
+            RouteNla::MultiPath(raw) => multipath = Some(raw.as_slice()),
+            _ => {}
+        }
+    }
+
+    // The local table only holds the host and broadcast routes of the
+    // addresses themselves.
+    if table == libc::RT_TABLE_LOCAL as u32 {
+        return Vec::new();
+    }
+
+    let destination = match destination {
+        Some(dst) if !is_default => {
+            smol_str::format_smolstr!("{}/{}", dst, header.destination_prefix_length)
+        }
+        _ => SmolStr::new_static("default"),
+    };
+    let route = Route {
+        destination,
+        gateway,
+        metric,
+        table: route::table_name(table),
+        protocol: route::protocol_name(header.protocol),
+        multipath: false,
+    };
+
+    let Some(mut raw) = multipath else {
+        return oif.map(|oif| (oif, route)).into_iter().collect();
+    };
+
+    // A list of struct rtnexthop { len: u16, flags: u8, hops: u8, ifindex: i32 },
+    // each followed by its own attributes.
+    let mut hops = Vec::new();
+    while raw.len() >= 8 {
+        let len = nla_u16(raw).unwrap_or(0) as usize;
+        if len < 8 || len > raw.len() {
+            break;
+        }
+        let ifindex = nla_u32(&raw[4..]).unwrap_or(0);
+        let gateway = NlasIterator::new(&raw[8..len])
+            .flatten()
+            .find(|nla| nla.kind() == RTA_GATEWAY)
+            .and_then(|nla| ip_str(nla.value()));
+        hops.push((
+            ifindex,
+            Route {
+                gateway,
+                multipath: true,
+                ..route.clone()
+            },
+        ));
+        raw = &raw[((len + 3) & !3).min(raw.len())..];
+    }
+    hops
+}
+
+/// Preferred and valid lifetimes from a `struct ifa_cacheinfo`, None for
+/// INFINITY_LIFE_TIME.
+fn parse_cacheinfo(bytes: &[u8]) -> (Option<u32>, Option<u32>) {
+    let lifetime = |offset: usize| {
+        bytes
+            .get(offset..offset + 4)
+            .and_then(nla_u32)
+            .filter(|&t| t != u32::MAX)
+    };
+    (lifetime(0), lifetime(4))
+}
+
+/// Names of the IFA_F_* bits set in `flags`, as `ip addr` prints them.
+fn addr_flag_names(flags: u32, family: i32) -> Vec<SmolStr> {
+    // IFA_F_SECONDARY and IFA_F_TEMPORARY share a bit.
+    let secondary = if family == libc::AF_INET6 {
+        "temporary"
+    } else {
+        "secondary"
+    };
+    [
+        (0x01, secondary),
+        (0x02, "nodad"),
+        (0x04, "optimistic"),
+        (0x08, "dadfailed"),
+        (0x10, "home"),
+        (0x20, "deprecated"),
+        (0x40, "tentative"),
+        (0x80, "permanent"),
+        (0x100, "mngtmpaddr"),
+        (0x200, "noprefixroute"),
+        (0x400, "autojoin"),
+        (0x800, "stable-privacy"),
+    ]
+    .iter()
+    .filter(|(bit, _)| flags & bit != 0)
+    .map(|(_, name)| SmolStr::new_static(name))
+    .collect()
+}
+
+fn scope_name(scope: u8) -> &'static str {
+    match scope {
+        libc::RT_SCOPE_UNIVERSE => "global",
+        libc::RT_SCOPE_SITE => "site",
+        libc::RT_SCOPE_LINK => "link",
+        libc::RT_SCOPE_HOST => "host",
+        _ => "nowhere",
+    }
+}
+
+const RTM_GETQDISC: u16 = 38;
+const RTM_GETTFILTER: u16 = 46;
+const TCMSG_LEN: usize = 20;
+
+/// struct tcmsg selecting the link `index` (0 for all) and `parent`.
+fn tcmsg(index: u32, parent: u32) -> [u8; TCMSG_LEN] {
+    let mut msg = [0u8; TCMSG_LEN];
+    msg[4..8].copy_from_slice(&index.to_ne_bytes());
+    msg[12..16].copy_from_slice(&parent.to_ne_bytes());
+    msg
+}
+
+/// One RTM_NEWQDISC message: the link index and parent, and the qdisc.
+fn parse_qdisc(msg: &[u8]) -> Option<(u32, u32, Qdisc)> {
+    const TCA_KIND: u16 = 1;
+    const TCA_OPTIONS: u16 = 2;
+    const TCA_STATS: u16 = 3;
+    const TCA_STATS2: u16 = 7;
+    const TCA_STATS_BASIC: u16 = 1;
+    const TCA_STATS_QUEUE: u16 = 3;
+
+    let header = msg.get(..TCMSG_LEN)?;
+    let index = u32::from_ne_bytes(header[4..8].try_into().ok()?);
+    let handle = u32::from_ne_bytes(header[8..12].try_into().ok()?);
+    let parent = u32::from_ne_bytes(header[12..16].try_into().ok()?);
+
+    let mut qdisc = Qdisc {
+        handle: tc::handle_str(handle),
+        parent: match parent {
+            tc::TC_H_ROOT => SmolStr::new_static("root"),
+            tc::TC_H_INGRESS => SmolStr::new_static("ingress"),
+            _ => tc::handle_str(parent),
+        },
+        ..Default::default()
+    };
+    let mut options = None;
+    let mut stats2 = false;
+    for nla in NlasIterator::new(&msg[TCMSG_LEN..]).flatten() {
+        let value = nla.value();
+        match nla.kind() {
+            TCA_KIND => {
+                let kind = value.split(|&b| b == 0).next().unwrap_or_default();
+                qdisc.kind = SmolStr::from(String::from_utf8_lossy(kind).as_ref());
+            }
+            TCA_OPTIONS => options = Some(value.to_vec()),
+            TCA_STATS2 => {
+                stats2 = true;
+                for stat in NlasIterator::new(value).flatten() {
+                    let v = stat.value();
+                    match stat.kind() {
+                        TCA_STATS_BASIC => {
+                            qdisc.bytes = nla_u64(v).unwrap_or(0);
+                            qdisc.packets = v.get(8..).and_then(nla_u32).unwrap_or(0).into();
+                        }
+                        TCA_STATS_QUEUE => {
+                            let field = |n: usize| v.get(n * 4..).and_then(nla_u32).unwrap_or(0);
+                            qdisc.qlen = field(0);
+                            qdisc.backlog = field(1);
+                            qdisc.drops = field(2);
+                            qdisc.requeues = field(3);
+                            qdisc.overlimits = field(4);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            // struct tc_stats, for kernels without TCA_STATS2.
+            TCA_STATS if !stats2 => {
+                let field = |off: usize| value.get(off..).and_then(nla_u32).unwrap_or(0);
+                qdisc.bytes = nla_u64(value).unwrap_or(0);
+                qdisc.packets = field(8).into();
+                qdisc.drops = field(12);
+                qdisc.overlimits = field(16);
+                qdisc.qlen = field(28);
+                qdisc.backlog = field(32);
+            }
+            _ => {}
+        }
+    }
+    if let Some(options) = options {
+        qdisc.params = qdisc_params(&qdisc.kind, &options);
+    }
+    // clsact shares the ingress parent, but is not an ingress qdisc.
+    if parent == tc::TC_H_INGRESS && qdisc.kind == "clsact" {
+        qdisc.parent = SmolStr::new_static("clsact");
+    }
+    Some((index, parent, qdisc))
+}
+
+/// The options worth a glance for the common qdiscs, from the TCA_OPTIONS
+/// payload. Kinds not listed here get none.
+fn qdisc_params(kind: &str, opts: &[u8]) -> Vec<SmolStr> {
+    use smol_str::format_smolstr;
+
+    let at = |off: usize| opts.get(off..).and_then(nla_u32);
+    let mut params = Vec::new();
+    match kind {
+        // struct tc_prio_qopt
+        "pfifo_fast" | "prio" => {
+            if let Some(bands) = at(0) {
+                params.push(format_smolstr!("bands {}", bands));
+            }
+        }
+        // struct tc_fifo_qopt
+        "pfifo" | "bfifo" => {
+            if let Some(limit) = at(0) {
+                let unit = if kind == "pfifo" { "p" } else { "b" };
+                params.push(format_smolstr!("limit {}{}", limit, unit));
+            }
+        }
+        // struct tc_mqprio_qopt
+        "mqprio" => {
+            if let Some(num_tc) = nla_u8(opts) {
+                params.push(format_smolstr!("num_tc {}", num_tc));
+            }
+        }
+        // struct tc_netem_qopt, followed by the TCA_NETEM_* attributes.
+        "netem" => {
+            const TCA_NETEM_LATENCY64: u16 = 10;
+            if let Some(limit) = at(4) {
+                params.push(format_smolstr!("limit {}", limit));
+            }
+            let latency = NlasIterator::new(opts.get(24..).unwrap_or_default())
+                .flatten()
+                .find(|n| n.kind() == TCA_NETEM_LATENCY64)
+                .and_then(|n| nla_u64(n.value()))
+                .filter(|&ns| ns > 0);
+            if let Some(ns) = latency {
+                params.push(format_smolstr!("delay {}", tc::usecs_str(ns / 1000)));
+            }
+            if let Some(loss) = at(8).filter(|&loss| loss > 0) {
+                let percent = loss as f64 * 100.0 / u32::MAX as f64;
+                params.push(format_smolstr!("loss {:.2}%", percent));
+            }
+        }
+        "tbf" => {
+            // TCA_TBF_PARMS is struct tc_tbf_qopt: the rate and peakrate
+            // tc_ratespec (rate at offset 8), then limit.
+            const TCA_TBF_PARMS: u16 = 1;
+            const TCA_TBF_RATE64: u16 = 4;
+            let mut rate = None;
+            let mut limit = None;
+            for nla in NlasIterator::new(opts).flatten() {
+                let value = nla.value();
+                match nla.kind() {
+                    TCA_TBF_PARMS => {
+                        rate = rate.or(value.get(8..).and_then(nla_u32).map(u64::from));
+                        limit = value.get(24..).and_then(nla_u32);
+                    }
+                    TCA_TBF_RATE64 => rate = nla_u64(value),
+                    _ => {}
+                }
+            }
+            if let Some(rate) = rate {
+                params.push(format_smolstr!("rate {}", tc::rate_str(rate)));
+            }
+            if let Some(limit) = limit {
+                params.push(format_smolstr!("limit {}b", limit));
+            }
+        }
+        "htb" => {
+            // TCA_HTB_INIT is struct tc_htb_glob: version, rate2quantum, defcls...
+            const TCA_HTB_INIT: u16 = 2;
+            let defcls = NlasIterator::new(opts)
+                .flatten()
+                .find(|n| n.kind() == TCA_HTB_INIT)
+                .and_then(|n| n.value().get(8..).and_then(nla_u32));
+            if let Some(defcls) = defcls {
+                params.push(format_smolstr!("default {:#x}", defcls));
+            }
+        }
+        "fq_codel" => {
+            const TCA_FQ_CODEL_TARGET: u16 = 1;
+            const TCA_FQ_CODEL_LIMIT: u16 = 2;
+            const TCA_FQ_CODEL_INTERVAL: u16 = 3;
+            const TCA_FQ_CODEL_ECN: u16 = 4;
+            const TCA_FQ_CODEL_FLOWS: u16 = 5;
+            const TCA_FQ_CODEL_QUANTUM: u16 = 6;
+            for nla in NlasIterator::new(opts).flatten() {
+                let Some(value) = nla_u32(nla.value()) else {
+                    continue;
+                };
+                params.push(match nla.kind() {
+                    TCA_FQ_CODEL_LIMIT => format_smolstr!("limit {}p", value),
+                    TCA_FQ_CODEL_FLOWS => format_smolstr!("flows {}", value),
+                    TCA_FQ_CODEL_QUANTUM => format_smolstr!("quantum {}", value),
+                    TCA_FQ_CODEL_TARGET => {
+                        format_smolstr!("target {}", tc::usecs_str(value.into()))
+                    }
+                    TCA_FQ_CODEL_INTERVAL => {
+                        format_smolstr!("interval {}", tc::usecs_str(value.into()))
+                    }
+                    TCA_FQ_CODEL_ECN if value != 0 => SmolStr::new_static("ecn"),
+                    _ => continue,
+                });
+            }
+        }
+        "fq" => {
+            const TCA_FQ_PLIMIT: u16 = 1;
+            const TCA_FQ_FLOW_PLIMIT: u16 = 2;
+            const TCA_FQ_QUANTUM: u16 = 3;
+            const TCA_FQ_FLOW_MAX_RATE: u16 = 7;
+            const TCA_FQ_BUCKETS_LOG: u16 = 8;
+            for nla in NlasIterator::new(opts).flatten() {
+                let Some(value) = nla_u32(nla.value()) else {
+                    continue;
+                };
+                params.push(match nla.kind() {
+                    TCA_FQ_PLIMIT => format_smolstr!("limit {}p", value),
+                    TCA_FQ_FLOW_PLIMIT => format_smolstr!("flow_limit {}p", value),
+                    TCA_FQ_QUANTUM => format_smolstr!("quantum {}", value),
+                    TCA_FQ_BUCKETS_LOG if value < 32 => {
+                        format_smolstr!("buckets {}", 1u32 << value)
+                    }
+                    TCA_FQ_FLOW_MAX_RATE if value != u32::MAX => {
+                        format_smolstr!("maxrate {}", tc::rate_str(value.into()))
+                    }
+                    _ => continue,
+                });
+            }
+        }
+        "cake" => {
+            const TCA_CAKE_BASE_RATE64: u16 = 2;
+            let rate = NlasIterator::new(opts)
+                .flatten()
+                .find(|n| n.kind() == TCA_CAKE_BASE_RATE64)
+                .and_then(|n| nla_u64(n.value()));
+            match rate {
+                Some(0) => params.push(SmolStr::new_static("unlimited")),
+                Some(rate) => params.push(format_smolstr!("bandwidth {}", tc::rate_str(rate))),
+                None => {}
+            }
+        }
+        _ => {}
+    }
+    params
+}
+
+/// IFLA_XDP: the program attached in each mode. With XDP_ATTACHED_MULTI the
+/// kernel reports one id per mode instead of a single IFLA_XDP_PROG_ID.
+fn parse_xdp(raw: &[u8]) -> Vec<XdpProg> {
+    const IFLA_XDP_ATTACHED: u16 = 2;
+    const IFLA_XDP_PROG_ID: u16 = 4;
+    const IFLA_XDP_DRV_PROG_ID: u16 = 5;
+    const IFLA_XDP_SKB_PROG_ID: u16 = 6;
+    const IFLA_XDP_HW_PROG_ID: u16 = 7;
+    const XDP_ATTACHED_DRV: u8 = 1;
+    const XDP_ATTACHED_SKB: u8 = 2;
+    const XDP_ATTACHED_HW: u8 = 3;
+
+    let mut attached = None;
+    let mut prog_id = None;
+    let mut by_mode = Vec::new();
+    for nla in NlasIterator::new(raw).flatten() {
+        let value = nla.value();
+        match nla.kind() {
+            IFLA_XDP_ATTACHED => attached = nla_u8(value),
+            IFLA_XDP_PROG_ID => prog_id = nla_u32(value),
+            IFLA_XDP_DRV_PROG_ID => by_mode.extend(nla_u32(value).map(|id| (XDP_ATTACHED_DRV, id))),
+            IFLA_XDP_SKB_PROG_ID => by_mode.extend(nla_u32(value).map(|id| (XDP_ATTACHED_SKB, id))),
+            IFLA_XDP_HW_PROG_ID => by_mode.extend(nla_u32(value).map(|id| (XDP_ATTACHED_HW, id))),
+            _ => {}
+        }
+    }
+    if by_mode.is_empty() {
+        by_mode.extend(attached.zip(prog_id));
+    }
+    by_mode
+        .into_iter()
+        .filter_map(|(mode, id)| {
+            Some(XdpProg {
+                mode: SmolStr::new_static(bpf::xdp_mode_name(mode)?),
+                prog: BpfProg::resolve(id),
+            })
+        })
+        .collect()
+}
+
+/// One RTM_NEWTFILTER message, when it is a cls_bpf filter with a program.
+fn parse_bpf_filter(msg: &[u8], direction: &'static str) -> Option<TcBpfProg> {
+    const TCA_KIND: u16 = 1;
+    const TCA_OPTIONS: u16 = 2;
+    const TCA_BPF_NAME: u16 = 7;
+    const TCA_BPF_FLAGS: u16 = 8;
+    const TCA_BPF_ID: u16 = 11;
+    const TCA_BPF_FLAG_ACT_DIRECT: u32 = 1;
+
+    let header = msg.get(..TCMSG_LEN)?;
+    let info = u32::from_ne_bytes(header[16..20].try_into().ok()?);
+
+    let mut is_bpf = false;
+    let mut id = None;
+    let mut filter_name = None;
+    let mut direct_action = false;
+    for nla in NlasIterator::new(&msg[TCMSG_LEN..]).flatten() {
+        match nla.kind() {
+            TCA_KIND => is_bpf = nla.value().starts_with(b"bpf\0"),
+            TCA_OPTIONS => {
+                for opt in NlasIterator::new(nla.value()).flatten() {
+                    let value = opt.value();
+                    match opt.kind() {
+                        TCA_BPF_ID => id = nla_u32(value),
+                        TCA_BPF_FLAGS => {
+                            direct_action =
+                                nla_u32(value).is_some_and(|f| f & TCA_BPF_FLAG_ACT_DIRECT != 0)
+                        }
+                        TCA_BPF_NAME => {
+                            let name = value.split(|&b| b == 0).next().unwrap_or_default();
+                            filter_name =
+                                Some(SmolStr::from(String::from_utf8_lossy(name).as_ref()));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    // The first message of each priority only announces the chain, with
+    // no program.
+    let id = id.filter(|_| is_bpf)?;
+    Some(TcBpfProg {
+        direction: SmolStr::new_static(direction),
+        pref: (info >> 16) as u16,
+        filter_name,
+        direct_action,
+        prog: BpfProg::resolve(id),
+    })
+}
+
+/// IFLA_VFINFO_LIST: one IFLA_VF_INFO per virtual function. Every setting is
+/// a struct starting with the VF number, followed by its value(s).
+fn parse_vfinfo_list(raw: &[u8]) -> Vec<VfInfo> {
+    const IFLA_VF_MAC: u16 = 1;
+    const IFLA_VF_VLAN: u16 = 2;
+    const IFLA_VF_SPOOFCHK: u16 = 4;
+    const IFLA_VF_LINK_STATE: u16 = 5;
+    const IFLA_VF_RATE: u16 = 6;
+    const IFLA_VF_STATS: u16 = 8;
+    const IFLA_VF_TRUST: u16 = 9;
+    const IFLA_VF_VLAN_LIST: u16 = 12;
+    const IFLA_VF_VLAN_INFO: u16 = 1;
+    // The setting reported by drivers that do not support it.
+    const UNSUPPORTED: u32 = u32::MAX;
+
+    let field = |value: &[u8], n: usize| value.get(n * 4..).and_then(nla_u32);
+    let setting = |value: &[u8]| {
+        field(value, 1)
+            .filter(|&v| v != UNSUPPORTED)
+            .map(|v| v != 0)
+    };
+
+    let mut vfs = Vec::new();
+    for info in NlasIterator::new(raw).flatten() {
+        let mut vf = VfInfo::default();
+        for nla in NlasIterator::new(info.value()).flatten() {
+            let value = nla.value();
+            match nla.kind() {
+                IFLA_VF_MAC => {
+                    vf.vf = field(value, 0).unwrap_or(0);
+                    // struct ifla_vf_mac has room for 32 bytes; Ethernet uses 6.
+                    vf.mac = value.get(4..10).map(format_hwaddr);
+                }
+                IFLA_VF_VLAN => {
+                    vf.vlan = field(value, 1);
+                    vf.qos = field(value, 2);
+                }
+                IFLA_VF_VLAN_LIST => {
+                    // Only the first entry: with a single VLAN per VF this is
+                    // the same one as IFLA_VF_VLAN, plus its protocol.
+                    vf.vlan_protocol = NlasIterator::new(value)
+                        .flatten()
+                        .find(|n| n.kind() == IFLA_VF_VLAN_INFO)
+                        .and_then(|n| n.value().get(12..14).map(|p| [p[0], p[1]]))
+                        .map(|p| linkinfo::vlan_protocol_name(u16::from_be_bytes(p)));
+                }
+                IFLA_VF_SPOOFCHK => vf.spoofchk = setting(value),
+                IFLA_VF_TRUST => vf.trust = setting(value),
+                IFLA_VF_LINK_STATE => {
+                    vf.link_state = field(value, 1)
+                        .and_then(sriov::link_state_name)
+                        .map(SmolStr::new_static)
+                }
+                IFLA_VF_RATE => {
+                    vf.min_tx_rate = field(value, 1);
+                    vf.max_tx_rate = field(value, 2);
+                }
+                IFLA_VF_STATS => vf.stats = Some(parse_vf_stats(value)),
+                _ => {}
+            }
+        }
+        vfs.push(vf);
+    }
+    vfs
+}
+
+/// IFLA_VF_STATS_* counters of a VF.
+fn parse_vf_stats(raw: &[u8]) -> VfStats {
+    const IFLA_VF_STATS_RX_PACKETS: u16 = 0;
+    const IFLA_VF_STATS_TX_PACKETS: u16 = 1;
+    const IFLA_VF_STATS_RX_BYTES: u16 = 2;
+    const IFLA_VF_STATS_TX_BYTES: u16 = 3;
+    const IFLA_VF_STATS_BROADCAST: u16 = 4;
+    const IFLA_VF_STATS_MULTICAST: u16 = 5;
+    const IFLA_VF_STATS_RX_DROPPED: u16 = 7;
+    const IFLA_VF_STATS_TX_DROPPED: u16 = 8;
+
+    let mut stats = VfStats::default();
+    for nla in NlasIterator::new(raw).flatten() {
+        let Some(value) = nla_u64(nla.value()) else {
+            continue;
+        };
+        match nla.kind() {
+            IFLA_VF_STATS_RX_PACKETS => stats.rx_packets = value,
+            IFLA_VF_STATS_TX_PACKETS => stats.tx_packets = value,
+            IFLA_VF_STATS_RX_BYTES => stats.rx_bytes = value,
+            IFLA_VF_STATS_TX_BYTES => stats.tx_bytes = value,
+            IFLA_VF_STATS_BROADCAST => stats.broadcast = value,
+            IFLA_VF_STATS_MULTICAST => stats.multicast = value,
+            IFLA_VF_STATS_RX_DROPPED => stats.rx_dropped = value,
+            IFLA_VF_STATS_TX_DROPPED => stats.tx_dropped = value,
+            _ => {}
+        }
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rtnetlink::packet::nlas::link::BridgeVlanInfo;
+
+    fn vlan_msg(entries: &[(u16, u16)]) -> LinkMessage {
+        let mut msg = LinkMessage::default();
+        msg.nlas.push(LinkNla::AfSpecBridge(
+            entries
+                .iter()
+                .map(|&(flags, vid)| AfSpecBridge::VlanInfo(BridgeVlanInfo { flags, vid }))
+                .collect(),
+        ));
+        msg
+    }
+
+    /// A netlink attribute, padded to 4 bytes.
+    fn nla(kind: u16, value: &[u8]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&((4 + value.len()) as u16).to_ne_bytes());
+        buf.extend_from_slice(&kind.to_ne_bytes());
+        buf.extend_from_slice(value);
+        buf.resize((buf.len() + 3) & !3, 0);
+        buf
+    }
+
+    fn u32s(values: &[u32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_ne_bytes()).collect()
+    }
+
+    fn params(kind: &str, opts: &[u8]) -> Vec<String> {
+        qdisc_params(kind, opts)
+            .iter()
+            .map(|p| p.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn qdisc_params_structs() {
+        // bands, then the 16 byte priomap.
+        let mut prio = u32s(&[3]);
+        prio.extend_from_slice(&[1, 2, 2, 2, 1, 2, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1]);
+        assert_eq!(params("pfifo_fast", &prio), ["bands 3"]);
+        assert_eq!(params("pfifo", &u32s(&[1000])), ["limit 1000p"]);
+        assert_eq!(params("bfifo", &u32s(&[1000])), ["limit 1000b"]);
+        assert_eq!(params("mqprio", &[4, 0, 0, 0]), ["num_tc 4"]);
+    }
+
+    #[test]
+    fn qdisc_params_netem() {
+        // latency, limit, loss, gap, duplicate, jitter; then the attributes.
+        let mut opts = u32s(&[0, 1000, u32::MAX / 100, 0, 0, 0]);
+        opts.extend(nla(10, &10_000_000u64.to_ne_bytes()));
+        assert_eq!(
+            params("netem", &opts),
+            ["limit 1000", "delay 10ms", "loss 1.00%"]
+        );
+        assert_eq!(
+            params("netem", &u32s(&[0, 1000, 0, 0, 0, 0])),
+            ["limit 1000"]
+        );
+    }
+
+    #[test]
+    fn qdisc_params_tbf() {
+        // tc_tbf_qopt: rate and peakrate tc_ratespec, limit, buffer, mtu.
+        let mut qopt = vec![0u8; 8];
+        qopt.extend(u32s(&[125_000, 0, 0, 0, 1600, 0, 0]));
+        assert_eq!(params("tbf", &nla(1, &qopt)), ["rate 1Mbit", "limit 1600b"]);
+
+        let mut opts = nla(1, &qopt);
+        opts.extend(nla(4, &1_250_000_000u64.to_ne_bytes()));
+        assert_eq!(params("tbf", &opts), ["rate 10Gbit", "limit 1600b"]);
+    }
+
+    #[test]
+    fn qdisc_params_attributes() {
+        let mut fq_codel = Vec::new();
+        for (kind, value) in [
+            (1, 5000u32),
+            (2, 10240),
+            (3, 100_000),
+            (4, 1),
+            (5, 1024),
+            (6, 1514),
+        ] {
+            fq_codel.extend(nla(kind, &value.to_ne_bytes()));
+        }
+        assert_eq!(
+            params("fq_codel", &fq_codel),
+            [
+                "target 5ms",
+                "limit 10240p",
+                "interval 100ms",
+                "ecn",
+                "flows 1024",
+                "quantum 1514"
+            ]
+        );
+
+        let mut fq = Vec::new();
+        for (kind, value) in [(1, 10000), (2, 100), (7, u32::MAX), (8, 10)] {
+            fq.extend(nla(kind, &value.to_ne_bytes()));
+        }
+        assert_eq!(
+            params("fq", &fq),
+            ["limit 10000p", "flow_limit 100p", "buckets 1024"]
+        );
+
+        assert_eq!(params("cake", &nla(2, &0u64.to_ne_bytes())), ["unlimited"]);
+        assert_eq!(
+            params("cake", &nla(2, &12_500_000u64.to_ne_bytes())),
+            ["bandwidth 100Mbit"]
+        );
+    }
+
+    #[test]
+    fn qdisc_params_unknown_or_truncated() {
+        assert!(params("sfq", &u32s(&[1, 2, 3])).is_empty());
+        assert!(params("pfifo", &[]).is_empty());
+        assert!(params("pfifo_fast", &[3, 0]).is_empty());
+    }
+
+    #[test]
+    fn vfinfo_list() {
+        let mut mac = u32s(&[1]);
+        mac.extend_from_slice(&[0x02, 0, 0, 0, 0, 0x01]);
+        mac.resize(4 + 32, 0);
+
+        let mut vlan_info = u32s(&[1, 10, 2]);
+        vlan_info.extend_from_slice(&0x88a8u16.to_be_bytes());
+
+        let mut stats = nla(0, &5u64.to_ne_bytes());
+        stats.extend(nla(8, &7u64.to_ne_bytes()));
+
+        let mut vf1 = nla(1, &mac);
+        vf1.extend(nla(2, &u32s(&[1, 10, 2])));
+        vf1.extend(nla(12, &nla(1, &vlan_info)));
+        vf1.extend(nla(4, &u32s(&[1, 1])));
+        vf1.extend(nla(9, &u32s(&[1, u32::MAX])));
+        vf1.extend(nla(5, &u32s(&[1, 0])));
+        vf1.extend(nla(6, &u32s(&[1, 0, 1000])));
+        vf1.extend(nla(8, &stats));
+
+        let mut raw = nla(1, &vf1);
+        let mut mac2 = u32s(&[2]);
+        mac2.resize(4 + 32, 0);
+        raw.extend(nla(1, &nla(1, &mac2)));
+
+        let vfs = parse_vfinfo_list(&raw);
+        assert_eq!(vfs.len(), 2);
+
+        let vf = &vfs[0];
+        assert_eq!(vf.vf, 1);
+        assert_eq!(vf.mac.as_deref(), Some("02:00:00:00:00:01"));
+        assert_eq!((vf.vlan, vf.qos), (Some(10), Some(2)));
+        assert_eq!(vf.vlan_protocol.as_deref(), Some("802.1ad"));
+        assert_eq!(vf.spoofchk, Some(true));
+        // u32::MAX: the driver does not support trust.
+        assert_eq!(vf.trust, None);
+        assert_eq!(vf.link_state.as_deref(), Some("auto"));
+        assert_eq!((vf.min_tx_rate, vf.max_tx_rate), (Some(0), Some(1000)));
+        let stats = vf.stats.as_ref().unwrap();
+        assert_eq!((stats.rx_packets, stats.tx_dropped), (5, 7));
+        assert_eq!(
+            vf.details_str(),
+            "vlan 10 qos 2 proto 802.1ad, spoofchk on, link auto, max_tx_rate 1000Mbps"
+        );
+
+        assert_eq!(vfs[1].vf, 2);
+        assert_eq!(vfs[1].details_str(), "");
+    }
+
+    fn route_msg(prefix_len: u8, protocol: u8, kind: u8, nlas: Vec<RouteNla>) -> RouteMessage {
+        let mut msg = RouteMessage::default();
+        msg.header.address_family = libc::AF_INET as u8;
+        msg.header.destination_prefix_length = prefix_len;
+        msg.header.protocol = protocol;
+        msg.header.kind = kind;
+        msg.header.table = libc::RT_TABLE_MAIN;
+        msg.nlas = nlas;
+        msg
+    }
+
+    #[test]
+    fn route_default_with_gateway() {
+        // RTPROT_DHCP, RTN_UNICAST
+        let msg = route_msg(
+            0,
+            16,
+            1,
+            vec![
+                RouteNla::Gateway(vec![192, 0, 2, 1]),
+                RouteNla::Priority(100),
+                RouteNla::Oif(2),
+                RouteNla::Table(254),
+            ],
+        );
+        let routes = parse_route(&msg);
+        assert_eq!(routes.len(), 1);
+        let (oif, route) = &routes[0];
+        assert_eq!(*oif, 2);
+        assert!(route.is_default());
+        assert_eq!(route.gateway.as_deref(), Some("192.0.2.1"));
+        assert_eq!(route.metric, Some(100));
+        assert_eq!(
+            (route.table.as_str(), route.protocol.as_str()),
+            ("main", "dhcp")
+        );
+        assert!(!route.multipath);
+        assert_eq!(route.details_str(), "via 192.0.2.1 metric 100 proto dhcp");
+    }
+
+    #[test]
+    fn route_multipath() {
+        // Two struct rtnexthop, each with an RTA_GATEWAY.
+        let mut hops = Vec::new();
+        for (ifindex, gateway) in [(3u32, [10, 0, 0, 1]), (4, [10, 0, 1, 1])] {
+            hops.extend_from_slice(&16u16.to_ne_bytes());
+            hops.extend_from_slice(&[0, 0]);
+            hops.extend_from_slice(&ifindex.to_ne_bytes());
+            hops.extend(nla(5, &gateway));
+        }
+        // RTPROT_BOOT, RTN_UNICAST
+        let msg = route_msg(
+            0,
+            3,
+            1,
+            vec![RouteNla::Priority(10), RouteNla::MultiPath(hops)],
+        );
+        let routes = parse_route(&msg);
+        let hops: Vec<_> = routes
+            .iter()
+            .map(|(oif, r)| (*oif, r.gateway.as_deref(), r.metric, r.multipath))
+            .collect();
+        assert_eq!(
+            hops,
+            [
+                (3, Some("10.0.0.1"), Some(10), true),
+                (4, Some("10.0.1.1"), Some(10), true)
+            ]
+        );
+    }
+
+    #[test]
+    fn route_dropped() {
+        // The local table.
+        let local = route_msg(
+            32,
+            2,
+            2,
+            vec![
+                RouteNla::Destination(vec![192, 0, 2, 2]),
+                RouteNla::Oif(2),
+                RouteNla::Table(255),
+            ],
+        );
+        assert!(parse_route(&local).is_empty());
+        // RTN_LOCAL, RTN_BROADCAST and RTN_UNREACHABLE defaults.
+        for kind in [2, 3, 7] {
+            let msg = route_msg(0, 3, kind, vec![RouteNla::Oif(2)]);
+            assert!(parse_route(&msg).is_empty());
+        }
+        // A static, non-default route is neither default nor learned.
+        let stat = route_msg(
+            24,
+            4,
+            1,
+            vec![RouteNla::Destination(vec![10, 1, 0, 0]), RouteNla::Oif(2)],
+        );
+        assert!(parse_route(&stat).is_empty());
+        // A connected prefix is kept.
+        let connected = route_msg(
+            24,
+            2,
+            1,
+            vec![RouteNla::Destination(vec![10, 1, 0, 0]), RouteNla::Oif(2)],
+        );
+        assert_eq!(parse_route(&connected)[0].1.destination, "10.1.0.0/24");
+    }
+
+    /// A struct rtnl_link_stats64 of `slots` counters, slot N holding 1 << N.
+    fn stats64(slots: usize) -> Vec<u8> {
+        (0..slots).flat_map(|n| (1u64 << n).to_ne_bytes()).collect()
+    }
+
+    #[test]
+    fn stats64_columns() {
+        let s = parse_stats64(&stats64(25)).unwrap();
+        let bit = |n: u32| 1u64 << n;
+        assert_eq!((s.rx_packets, s.tx_packets), (bit(0), bit(1)));
+        assert_eq!((s.rx_bytes, s.tx_bytes), (bit(2), bit(3)));
+        assert_eq!((s.rx_errors, s.tx_errors), (bit(4), bit(5)));
+        // /proc/net/dev adds rx_missed_errors to the drops.
+        assert_eq!(s.rx_dropped, bit(6) + bit(15));
+        assert_eq!(s.tx_dropped, bit(7));
+        assert_eq!(s.rx_multicast, bit(8));
+        assert_eq!(s.tx_collisions, bit(9));
+        // length, over, crc and frame errors.
+        assert_eq!(s.rx_frame, bit(10) + bit(11) + bit(12) + bit(13));
+        assert_eq!(s.rx_fifo, bit(14));
+        // carrier, aborted, window and heartbeat errors.
+        assert_eq!(s.tx_carrier, bit(16) + bit(17) + bit(19) + bit(20));
+        assert_eq!(s.tx_fifo, bit(18));
+        assert_eq!((s.rx_compressed, s.tx_compressed), (bit(21), bit(22)));
+        assert_eq!(s.rx_missed, Some(bit(15)));
+        assert_eq!(s.rx_nohandler, Some(bit(23)));
+        assert_eq!(s.rx_otherhost_dropped, Some(bit(24)));
+    }
+
+    #[test]
+    fn stats64_older_kernels() {
+        let s = parse_stats64(&stats64(24)).unwrap();
+        assert_eq!(s.rx_nohandler, Some(1 << 23));
+        assert_eq!(s.rx_otherhost_dropped, None);
+
+        let s = parse_stats64(&stats64(23)).unwrap();
+        assert_eq!(s.tx_compressed, 1 << 22);
+        assert_eq!((s.rx_nohandler, s.rx_otherhost_dropped), (None, None));
+
+        assert!(parse_stats64(&stats64(22)).is_none());
+    }
+
+    #[test]
+    fn bridge_vlan_range_pair() {
+        // 1 PVID untagged, then 10-20 as a RANGE_BEGIN/RANGE_END pair.
+        let table = parse_bridge_vlans(&vlan_msg(&[(0x6, 1), (0x8, 10), (0x10, 20), (0, 100)]));
+        assert_eq!(
+            linkinfo::bridge_vlans_str(&table),
+            "1 PVID untagged,10-20,100"
+        );
+    }
+
+    #[test]
+    fn bridge_vlan_range_end_without_begin() {
+        let table = parse_bridge_vlans(&vlan_msg(&[(0x10, 20)]));
+        assert_eq!(linkinfo::bridge_vlans_str(&table), "20");
+    }
+}
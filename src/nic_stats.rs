@@ -0,0 +1,150 @@
+//! Driver-specific NIC statistics, as reported by `ethtool -S`.
+//!
+//! Drivers name their per-queue counters in a few different ways
+//! (`rx_queue_3_drops`, `rx3_packets`, `tx-0.tx_bytes`, `[2]: rx_ucast_packets`);
+//! these are grouped by queue, everything else is kept as a flat list.
+
+use serde::Serialize;
+use smol_str::SmolStr;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NicCounter {
+    pub name: SmolStr,
+    pub value: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueStats {
+    pub direction: SmolStr, // rx, tx, ch... or empty when the driver does not say
+    pub queue: u32,
+    pub counters: Vec<NicCounter>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NicStats {
+    pub counters: Vec<NicCounter>,
+    pub queues: Vec<QueueStats>,
+}
+
+impl NicStats {
+    /// Group the raw counters, keeping only those whose name contains `filter`.
+    pub fn new(raw: Vec<(SmolStr, u64)>, filter: &str) -> Self {
+        let mut stats = NicStats::default();
+
+        for (name, value) in raw {
+            if !name.contains(filter) {
+                continue;
+            }
+
+            match split_queue(&name) {
+                Some((direction, queue, counter)) => {
+                    let counter = NicCounter {
+                        name: SmolStr::from(counter),
+                        value,
+                    };
+                    match stats
+                        .queues
+                        .iter_mut()
+                        .find(|q| q.direction == direction && q.queue == queue)
+                    {
+                        Some(q) => q.counters.push(counter),
+                        None => stats.queues.push(QueueStats {
+                            direction: SmolStr::from(direction),
+                            queue,
+                            counters: vec![counter],
+                        }),
+                    }
+                }
+                None => stats.counters.push(NicCounter { name, value }),
+            }
+        }
+
+        stats
+            .queues
+            .sort_by(|a, b| (&a.direction, a.queue).cmp(&(&b.direction, b.queue)));
+        stats
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counters.is_empty() && self.queues.is_empty()
+    }
+}
+
+/// Split a per-queue counter name into direction, queue number and counter.
+fn split_queue(name: &str) -> Option<(&str, u32, &str)> {
+    // bnxt: "[3]: rx_ucast_packets"
+    if let Some(rest) = name.strip_prefix('[') {
+        let (queue, counter) = rest.split_once("]: ")?;
+        return Some(("", queue.parse().ok()?, counter));
+    }
+
+    // virtio, ixgbe, ice...: "rx_queue_3_drops"
+    if let Some((direction, rest)) = name.split_once("_queue_") {
+        let (queue, counter) = rest.split_once('_')?;
+        return Some((direction, queue.parse().ok()?, counter));
+    }
+
+    // i40e: "tx-0.tx_packets"
+    if let Some((prefix, counter)) = name.split_once('.') {
+        let (direction, queue) = prefix.split_once('-')?;
+        let counter = counter
+            .strip_prefix(direction)
+            .and_then(|c| c.strip_prefix('_'))
+            .unwrap_or(counter);
+        return Some((direction, queue.parse().ok()?, counter));
+    }
+
+    // mlx5: "rx3_packets", "ch0_events"
+    for direction in ["rx", "tx", "ch"] {
+        if let Some(rest) = name.strip_prefix(direction) {
+            let (queue, counter) = rest.split_once('_')?;
+            if let Ok(queue) = queue.parse() {
+                return Some((direction, queue, counter));
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_queue_formats() {
+        assert_eq!(
+            split_queue("rx_queue_0_packets"),
+            Some(("rx", 0, "packets"))
+        );
+        assert_eq!(
+            split_queue("tx_queue_12_xdp_tx"),
+            Some(("tx", 12, "xdp_tx"))
+        );
+        assert_eq!(split_queue("tx-3.bytes"), Some(("tx", 3, "bytes")));
+        assert_eq!(split_queue("tx-0.tx_packets"), Some(("tx", 0, "packets")));
+        assert_eq!(split_queue("rx0_bytes"), Some(("rx", 0, "bytes")));
+        assert_eq!(split_queue("ch3_events"), Some(("ch", 3, "events")));
+        assert_eq!(
+            split_queue("[3]: rx_ucast_packets"),
+            Some(("", 3, "rx_ucast_packets"))
+        );
+    }
+
+    #[test]
+    fn split_queue_rejects_other_counters() {
+        for name in [
+            "rx_bytes",
+            "tx_packets",
+            "rx_queue_x_packets",
+            "rxvlan_bytes",
+            "checksum_errors",
+            "tx_timeout.count",
+            "rx-csum.errors",
+            "[x]: rx_packets",
+            "",
+        ] {
+            assert_eq!(split_queue(name), None, "{}", name);
+        }
+    }
+}
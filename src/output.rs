@@ -0,0 +1,207 @@
+use crate::filter::CollectedInterface;
+use anyhow::Result;
+use serde::Serialize;
+use std::io::Write;
+
+/// Version of the JSON document layout. Bump it whenever a field is renamed,
+/// removed or changes type; adding new fields keeps the same version.
+///
+/// 2: `altname` (string or null) became `altnames` (list).
+/// 3: `flags_str` became `flags` (list); `mtu`, `metric`, `media` and the IPv4
+///    `prefix` are null instead of 0 or "unknown" when not known.
+pub const JSON_VERSION: u32 = 3;
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    version: u32,
+    interfaces: &'a [CollectedInterface],
+}
+
+pub fn print_json(interfaces: &[CollectedInterface]) -> Result<()> {
+    let report = JsonReport {
+        version: JSON_VERSION,
+        interfaces,
+    };
+
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    serde_json::to_writer_pretty(&mut out, &report)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+const BRIEF_SEPARATOR: &str = "  ";
+
+struct BriefColumn {
+    header: &'static str,
+    min_width: Option<usize>, // None: never narrowed
+    cells: Vec<String>,
+}
+
+impl BriefColumn {
+    fn new(header: &'static str, min_width: Option<usize>) -> Self {
+        Self {
+            header,
+            min_width,
+            cells: Vec::new(),
+        }
+    }
+
+    fn natural_width(&self) -> usize {
+        self.cells
+            .iter()
+            .map(|c| c.chars().count())
+            .chain(std::iter::once(self.header.len()))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// One line per interface, in the spirit of `ip -br`.
+pub fn print_brief(interfaces: &[CollectedInterface]) {
+    use owo_colors::OwoColorize;
+
+    let mut columns = [
+        BriefColumn::new("NAME", Some(8)),
+        BriefColumn::new("STATE", None),
+        BriefColumn::new("NETNS", Some(5)),
+        BriefColumn::new("MAC", Some(8)),
+        BriefColumn::new("IPV4", Some(9)),
+        BriefColumn::new("IPV6", Some(9)),
+        BriefColumn::new("DRIVER", Some(6)),
+        BriefColumn::new("SPEED", None),
+        BriefColumn::new("MTU", None),
+    ];
+
+    for info in interfaces {
+        let state = if info.link_detected {
+            "UP"
+        } else if info.is_up {
+            "NO-CARRIER"
+        } else {
+            "DOWN"
+        };
+        let cells = [
+            info.name.to_string(),
+            state.to_string(),
+            or_dash(info.netns.as_deref()),
+            or_dash(info.mac.as_deref()),
+            or_dash(info.ipv4.first().map(|a| a.cidr()).as_deref()),
+            or_dash(
+                info.ipv6
+                    .first()
+                    .map(|a| format!("{}/{}", a.addr, a.prefix))
+                    .as_deref(),
+            ),
+            or_dash(info.driver_info.as_ref().map(|d| d.driver.as_str())),
+            or_dash(info.speed.map(format_speed).as_deref()),
+            or_dash(info.mtu.map(|mtu| mtu.to_string()).as_deref()),
+        ];
+        for (col, cell) in columns.iter_mut().zip(cells) {
+            col.cells.push(cell);
+        }
+    }
+
+    let mut widths: Vec<usize> = columns.iter().map(|c| c.natural_width()).collect();
+    if let Some(max) = terminal_width() {
+        let mins: Vec<usize> = columns
+            .iter()
+            .zip(&widths)
+            .map(|(c, &w)| c.min_width.map_or(w, |m| m.min(w)))
+            .collect();
+        shrink_to_fit(&mut widths, &mins, max);
+    }
+
+    let last = columns.len() - 1;
+    let header: Vec<String> = columns
+        .iter()
+        .zip(&widths)
+        .enumerate()
+        .map(|(i, (col, &w))| pad(col.header, w, i == last))
+        .collect();
+    println!("{}", header.join(BRIEF_SEPARATOR).bold());
+
+    for (row, info) in interfaces.iter().enumerate() {
+        let mut line = Vec::with_capacity(columns.len());
+        for (i, (col, &w)) in columns.iter().zip(&widths).enumerate() {
+            let cell = pad(&col.cells[row], w, i == last);
+            let cell = match i {
+                0 if info.link_detected => cell.bold().bright_blue().to_string(),
+                0 => cell.blue().to_string(),
+                1 => cell.bright_black().to_string(),
+                3..=5 => cell.blue().to_string(),
+                _ => cell,
+            };
+            line.push(cell);
+        }
+        println!("{}", line.join(BRIEF_SEPARATOR));
+    }
+}
+
+fn or_dash(value: Option<&str>) -> String {
+    match value {
+        Some(v) if !v.is_empty() => v.to_string(),
+        _ => "-".to_string(),
+    }
+}
+
+/// Format a speed in Mb/s the way link partners are usually described (1G, 2.5G, 100M).
+pub fn format_speed(mbps: u32) -> String {
+    if mbps >= 1000 && mbps.is_multiple_of(1000) {
+        format!("{}G", mbps / 1000)
+    } else if mbps >= 1000 {
+        format!("{}G", mbps as f64 / 1000.0)
+    } else {
+        format!("{}M", mbps)
+    }
+}
+
+/// Truncate `text` to `width` characters (marking the cut with an ellipsis)
+/// and pad it to exactly `width`, unless it is the last column.
+fn pad(text: &str, width: usize, last: bool) -> String {
+    let len = text.chars().count();
+    if len > width {
+        let mut cut: String = text.chars().take(width.saturating_sub(1)).collect();
+        cut.push('…');
+        cut
+    } else if last {
+        text.to_string()
+    } else {
+        format!("{}{}", text, " ".repeat(width - len))
+    }
+}
+
+/// Narrow the widest columns, one character at a time, until the table fits
+/// in `max_total` or every column has reached its minimum width.
+fn shrink_to_fit(widths: &mut [usize], mins: &[usize], max_total: usize) {
+    let sep = BRIEF_SEPARATOR.len() * widths.len().saturating_sub(1);
+    let mut total: usize = widths.iter().sum::<usize>() + sep;
+
+    while total > max_total {
+        let widest = widths
+            .iter()
+            .zip(mins)
+            .enumerate()
+            .filter(|(_, (&w, &min))| w > min)
+            .max_by_key(|(_, (&w, _))| w)
+            .map(|(i, _)| i);
+
+        match widest {
+            Some(i) => {
+                widths[i] -= 1;
+                total -= 1;
+            }
+            None => break,
+        }
+    }
+}
+
+/// Width of the terminal attached to stdout, if any.
+fn terminal_width() -> Option<usize> {
+    let mut ws: libc::winsize = unsafe { std::mem::zeroed() };
+    let ret = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut ws) };
+    if ret == 0 && ws.ws_col > 0 {
+        return Some(ws.ws_col as usize);
+    }
+    None
+}
@@ -1,5 +1,6 @@
 #[cfg(not(target_os = "macos"))]
 use anyhow::Result;
+use serde::Serialize;
 #[cfg(not(target_os = "macos"))]
 use smol_str::SmolStr;
 #[cfg(not(target_os = "macos"))]
@@ -79,7 +80,7 @@ impl PciDb {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct PciDeviceInfo {
     pub vendor_id: u16,
     pub device_id: u16,
@@ -95,14 +96,36 @@ pub struct PciDeviceInfo {
     pub device: Option<u8>,
     pub function: Option<u8>,
     pub driver: Option<String>,
-    #[allow(dead_code)]
     pub numa_node: Option<i32>,
-    #[allow(dead_code)]
     pub irq: Option<u32>,
+    pub sriov: Option<Sriov>,   // the device is an SR-IOV physical function
+    pub physfn: Option<PhysFn>, // the device is a virtual function
+}
+
+/// SR-IOV capability of a physical function, from sysfs.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Sriov {
+    pub numvfs: u32,
+    pub totalvfs: u32,
+    pub vfs: Vec<VirtFn>, // the device/virtfnN links
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VirtFn {
+    pub index: u32,
+    pub pci_address: String,
+    pub netdev: Option<String>,
+}
+
+/// The physical function a VF belongs to (device/physfn).
+#[derive(Debug, Clone, Serialize)]
+pub struct PhysFn {
+    pub pci_address: String,
+    pub netdev: Option<String>,
+    pub vf_index: Option<u32>,
 }
 
 impl PciDeviceInfo {
-    #[allow(dead_code)]
     pub fn format_class(&self) -> String {
         if let (Some(class), Some(subclass)) = (self.class, self.subclass) {
             match (class, subclass) {
@@ -190,6 +213,7 @@ pub fn get_pci_devices() -> Result<HashMap<SmolStr, PciDeviceInfo>> {
                     use smol_str::format_smolstr;
 
                     let key = format_smolstr!("{:04x}:{:02x}:{:02x}.{}", dm, b, dv, f);
+                    read_sysfs_attrs(&key, &mut info);
                     devices.insert(key, info);
                 }
             }
@@ -202,6 +226,115 @@ pub fn get_pci_devices() -> Result<HashMap<SmolStr, PciDeviceInfo>> {
     Ok(devices)
 }
 
+#[cfg(target_os = "linux")]
+fn read_sysfs_attrs(pci_addr: &str, info: &mut PciDeviceInfo) {
+    let read = |attr: &str| {
+        std::fs::read_to_string(format!("/sys/bus/pci/devices/{}/{}", pci_addr, attr))
+            .ok()
+            .map(|s| s.trim().to_string())
+    };
+
+    // numa_node is -1 when the platform has no NUMA information
+    info.numa_node = read("numa_node")
+        .and_then(|s| s.parse::<i32>().ok())
+        .filter(|n| *n >= 0);
+    info.irq = read("irq")
+        .and_then(|s| s.parse::<u32>().ok())
+        .filter(|n| *n != 0);
+}
+
+/// SR-IOV side of PCI device `pci_addr`: its VFs when it is a physical
+/// function, its PF when it is a virtual function. Only done for the
+/// interfaces being shown, as a PF can have hundreds of VFs.
+#[cfg(target_os = "linux")]
+fn read_sriov_attrs(pci_addr: &str, info: &mut PciDeviceInfo) {
+    let read = |attr: &str| {
+        std::fs::read_to_string(format!("/sys/bus/pci/devices/{}/{}", pci_addr, attr))
+            .ok()
+            .map(|s| s.trim().to_string())
+    };
+
+    let totalvfs = read("sriov_totalvfs").and_then(|s| s.parse::<u32>().ok());
+    if let Some(totalvfs) = totalvfs.filter(|n| *n > 0) {
+        info.sriov = Some(Sriov {
+            numvfs: read("sriov_numvfs")
+                .and_then(|s| s.parse::<u32>().ok())
+                .unwrap_or(0),
+            totalvfs,
+            vfs: virtfns(pci_addr),
+        });
+    }
+
+    if let Some(pf) = link_target(&format!("/sys/bus/pci/devices/{}/physfn", pci_addr)) {
+        info.physfn = Some(PhysFn {
+            vf_index: vf_index(&pf, pci_addr),
+            netdev: netdev_of(&pf),
+            pci_address: pf,
+        });
+    }
+}
+
+/// The VFs of physical function `pci_addr`, by VF index.
+#[cfg(target_os = "linux")]
+fn virtfns(pci_addr: &str) -> Vec<VirtFn> {
+    let Ok(entries) = std::fs::read_dir(format!("/sys/bus/pci/devices/{}", pci_addr)) else {
+        return Vec::new();
+    };
+    let mut vfs: Vec<VirtFn> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let index = name.to_str()?.strip_prefix("virtfn")?.parse().ok()?;
+            let pci_address = link_target(entry.path().to_str()?)?;
+            Some(VirtFn {
+                index,
+                netdev: netdev_of(&pci_address),
+                pci_address,
+            })
+        })
+        .collect();
+    vfs.sort_by_key(|vf| vf.index);
+    vfs
+}
+
+/// Index N of the device/virtfnN link of physical function `pf` that points
+/// to `vf_addr`.
+#[cfg(target_os = "linux")]
+fn vf_index(pf: &str, vf_addr: &str) -> Option<u32> {
+    let entries = std::fs::read_dir(format!("/sys/bus/pci/devices/{}", pf)).ok()?;
+    entries.flatten().find_map(|entry| {
+        let name = entry.file_name();
+        let index = name.to_str()?.strip_prefix("virtfn")?.parse().ok()?;
+        (link_target(entry.path().to_str()?)? == vf_addr).then_some(index)
+    })
+}
+
+/// Last component of the symlink at `path`: a PCI address for physfn and virtfnN.
+#[cfg(target_os = "linux")]
+fn link_target(path: &str) -> Option<String> {
+    let target = std::fs::read_link(path).ok()?;
+    Some(target.file_name()?.to_str()?.to_string())
+}
+
+/// The network interface of PCI device `pci_addr`, if it has one in this
+/// namespace.
+#[cfg(target_os = "linux")]
+fn netdev_of(pci_addr: &str) -> Option<String> {
+    let entries = std::fs::read_dir(format!("/sys/bus/pci/devices/{}/net", pci_addr)).ok()?;
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter_map(|e| e.file_name().to_str().map(String::from))
+        .collect();
+    names.sort();
+    names.into_iter().next()
+}
+
+#[cfg(all(not(target_os = "linux"), not(target_os = "macos")))]
+fn read_sysfs_attrs(_pci_addr: &str, _info: &mut PciDeviceInfo) {}
+
+#[cfg(all(not(target_os = "linux"), not(target_os = "macos")))]
+fn read_sriov_attrs(_pci_addr: &str, _info: &mut PciDeviceInfo) {}
+
 #[cfg(not(target_os = "macos"))]
 pub fn find_pci_info_for_interface(
     interface_name: &str,
@@ -214,15 +347,14 @@ pub fn find_pci_info_for_interface(
 
     let clean_bus = bus_info.trim_start_matches("pci@");
 
-    let pci_addr = if let Some(addr) = parse_pci_address(clean_bus) {
-        addr
-    } else if let Some(addr) = extract_pci_from_sysfs(interface_name) {
-        addr
-    } else {
-        return None;
+    let pci_addr = match parse_pci_address(clean_bus) {
+        Some(addr) => addr,
+        None => extract_pci_from_sysfs(interface_name)?,
     };
 
-    pci_devices.get(&pci_addr).cloned()
+    let mut info = pci_devices.get(&pci_addr).cloned()?;
+    read_sriov_attrs(&pci_addr, &mut info);
+    Some(info)
 }
 
 #[cfg(not(target_os = "macos"))]
@@ -318,6 +450,6 @@ fn extract_pci_from_sysfs(interface_name: &str) -> Option<SmolStr> {
 }
 
 #[cfg(all(not(target_os = "linux"), not(target_os = "macos")))]
-fn extract_pci_from_sysfs(_interface_name: &str) -> Option<String> {
+fn extract_pci_from_sysfs(_interface_name: &str) -> Option<SmolStr> {
     None
 }
@@ -1,20 +1,53 @@
 use anyhow::Result;
+use serde::Serialize;
 use smol_str::SmolStr;
-use std::collections::HashSet;
 
+use crate::bpf::{TcBpfProg, XdpProg};
+use crate::ifr::Inet4Addr;
+use crate::linkinfo::{BridgeVlan, LinkInfo, SlaveInfo};
 #[cfg(target_os = "macos")]
 use crate::macos;
+use crate::neigh::Neighbor;
+use crate::route::Route;
+use crate::sriov::VfInfo;
+use crate::tc::Qdisc;
 
-// This struct will hold the interface name and its network namespace, if any.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct LinuxNic {
+// One network interface as seen by a single dump of the namespace it lives in.
+#[derive(Debug, Clone, Default)]
+pub struct Link {
+    pub index: u32,
     pub name: SmolStr,
-    pub netns: Option<SmolStr>,
+    pub flags: u32,
+    pub carrier: Option<bool>, // IFLA_CARRIER
+    pub mac: Option<SmolStr>,
+    pub mtu: Option<u32>,
+    pub altnames: Vec<SmolStr>,
+    pub ipv4: Vec<Inet4Addr>,
+    pub ipv6: Vec<Inet6Addr>,
+    pub stats: Option<Stats>,
+    pub link_info: Option<LinkInfo>,
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub parent: Option<u32>, // IFLA_LINK
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub link_netnsid: Option<i32>, // IFLA_LINK_NETNSID: IFLA_LINK is in another netns
+    pub vlans: Vec<SmolStr>, // VLAN sub-interfaces stacked on this link
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub master: Option<u32>, // IFLA_MASTER
+    pub slave_info: Option<SlaveInfo>,
+    pub slaves: Vec<SmolStr>, // links enslaved to this one
+    pub bridge_vlans: Vec<BridgeVlan>,
+    pub routes: Vec<Route>, // default, connected and RA/DHCP routes through this link
+    pub neighbors: Option<Vec<Neighbor>>, // only with --neigh
+    pub qdiscs: Vec<Qdisc>, // only with details
+    pub xdp: Vec<XdpProg>,  // IFLA_XDP, one per attach mode
+    pub tc_bpf: Vec<TcBpfProg>, // BPF filters of the clsact/ingress qdisc
+    pub vfs: Vec<VfInfo>,   // IFLA_VFINFO_LIST of an SR-IOV physical function
 }
 
+/// Network namespaces to scan: `None` is the current one, followed by the
+/// named namespaces under /var/run/netns when we are root in the root netns.
 #[cfg(target_os = "linux")]
-pub fn get_if_list() -> Result<Vec<LinuxNic>> {
-    use nix::sched::{setns, CloneFlags};
+pub fn get_netns_list() -> Result<Vec<Option<SmolStr>>> {
     use std::fs;
 
     use std::os::unix::fs::MetadataExt; // for ino()
@@ -31,94 +64,141 @@ pub fn get_if_list() -> Result<Vec<LinuxNic>> {
         self_meta.ino() == init_meta.ino()
     }
 
-    let mut nics = Vec::new();
-    let mut seen_nics = HashSet::new();
+    let mut namespaces = vec![None];
 
     // If we are not in the root netns, or we are not euid 0, just list local interfaces.
     if !is_root_netns() || !nix::unistd::geteuid().is_root() {
-        for ifa in nix::ifaddrs::getifaddrs()? {
-            let nic = LinuxNic {
-                name: SmolStr::from(ifa.interface_name),
-                netns: None, // No specific namespace context from this perspective
-            };
-            if seen_nics.insert(nic.clone()) {
-                nics.push(nic);
-            }
-        }
-        nics.sort_by(|a, b| a.name.cmp(&b.name));
-        return Ok(nics);
-    }
-
-    // We are in the root netns and we are root. Let's scan all namespaces.
-
-    // 1. Get interfaces from the root namespace
-    for ifa in nix::ifaddrs::getifaddrs()? {
-        let nic = LinuxNic {
-            name: SmolStr::from(ifa.interface_name),
-            netns: None,
-        };
-        if seen_nics.insert(nic.clone()) {
-            nics.push(nic);
-        }
+        return Ok(namespaces);
     }
 
-    // 2. Scan and switch to other namespaces
     let ns_dir = Path::new("/var/run/netns");
     if ns_dir.exists() {
-        let original_ns = fs::File::open("/proc/self/ns/net")?;
-        for entry in fs::read_dir(ns_dir)?.flatten() {
-            let ns_name_os = entry.file_name();
-            let ns_name = ns_name_os.to_string_lossy();
-            if let Ok(ns_file) = fs::File::open(entry.path()) {
-                // Switch, get interfaces, switch back
-                if setns(ns_file, CloneFlags::CLONE_NEWNET).is_ok() {
-                    if let Ok(ifaddrs) = nix::ifaddrs::getifaddrs() {
-                        for ifa in ifaddrs {
-                            let nic = LinuxNic {
-                                name: SmolStr::from(ifa.interface_name),
-                                netns: Some(SmolStr::from(ns_name.as_ref())),
-                            };
-                            if seen_nics.insert(nic.clone()) {
-                                nics.push(nic);
-                            }
-                        }
-                    }
-                    // Switch back to original ns
-                    let _ = setns(&original_ns, CloneFlags::CLONE_NEWNET);
-                }
-            }
-        }
+        let mut names: Vec<SmolStr> = fs::read_dir(ns_dir)?
+            .flatten()
+            .map(|entry| SmolStr::from(entry.file_name().to_string_lossy().as_ref()))
+            .collect();
+        names.sort();
+        namespaces.extend(names.into_iter().map(Some));
     }
 
-    nics.sort_by_key(|k| k.name.clone());
-    Ok(nics)
+    Ok(namespaces)
 }
 
 #[cfg(not(target_os = "linux"))]
-pub fn get_if_list() -> Result<Vec<LinuxNic>> {
-    let addrs = nix::ifaddrs::getifaddrs()?;
-    let mut names = HashSet::new();
-    for ifa in addrs {
-        names.insert(ifa.interface_name);
-    }
-    let mut ret: Vec<SmolStr> = names.into_iter().map(SmolStr::from).collect();
-    ret.sort();
-    Ok(ret
-        .into_iter()
-        .map(|name| LinuxNic {
+pub fn get_netns_list() -> Result<Vec<Option<SmolStr>>> {
+    Ok(vec![None])
+}
+
+/// Build the link list with a single getifaddrs() pass.
+#[cfg(not(target_os = "linux"))]
+pub fn get_links() -> Result<Vec<Link>> {
+    use smol_str::format_smolstr;
+    use std::collections::BTreeMap;
+
+    let mut links: BTreeMap<SmolStr, Link> = BTreeMap::new();
+
+    for ifa in nix::ifaddrs::getifaddrs()? {
+        let name = SmolStr::from(ifa.interface_name.as_str());
+        let link = links.entry(name.clone()).or_insert_with(|| Link {
             name,
-            netns: None,
-        })
-        .collect())
+            ..Default::default()
+        });
+        link.flags = ifa.flags.bits() as u32;
+
+        let Some(address) = ifa.address else {
+            continue;
+        };
+
+        if let Some(mac) = address.as_link_addr().and_then(|l| l.addr()) {
+            let s = mac
+                .iter()
+                .map(|b| format_smolstr!("{:02x}", b))
+                .collect::<Vec<_>>()
+                .join(":");
+            link.mac = Some(s.into());
+            if let Ok(index) = nix::net::if_::if_nametoindex(link.name.as_str()) {
+                link.index = index;
+            }
+        } else if let Some(sockaddr) = address.as_sockaddr_in() {
+            let ip = std::net::Ipv4Addr::from(sockaddr.ip());
+            let mask_opt = ifa
+                .netmask
+                .as_ref()
+                .and_then(|a| a.as_sockaddr_in().map(|s| s.ip()));
+
+            if let Some(mask_u32) = mask_opt {
+                let mask_ip = std::net::Ipv4Addr::from(mask_u32);
+                link.ipv4.push(Inet4Addr {
+                    addr: SmolStr::from(ip.to_string()),
+                    mask: Some(SmolStr::from(mask_ip.to_string())),
+                    prefix: Some(u32::from(mask_ip).count_ones()),
+                    ..Default::default()
+                });
+            } else {
+                link.ipv4.push(Inet4Addr {
+                    addr: SmolStr::from(ip.to_string()),
+                    ..Default::default()
+                });
+            }
+        } else if let Some(sockaddr) = address.as_sockaddr_in6() {
+            let ip = sockaddr.ip();
+            // Calculate prefix len from netmask if available
+            let mut prefix = 0;
+            if let Some(netmask) = ifa.netmask.as_ref().and_then(|a| a.as_sockaddr_in6()) {
+                prefix = u128::from(netmask.ip()).count_ones();
+            }
+            // Simplified scope detection
+            let scope = if ip.is_loopback() {
+                "host"
+            } else if ip.is_unicast_link_local() {
+                "link"
+            } else if ip.octets()[0] == 0xfe && (ip.octets()[1] & 0xc0) == 0xc0 {
+                "site"
+            } else if ip.is_multicast() {
+                "multicast"
+            } else {
+                "global"
+            };
+
+            link.ipv6.push(Inet6Addr {
+                addr: SmolStr::from(ip.to_string()),
+                prefix,
+                scope: SmolStr::from(scope),
+                flags: Vec::new(),
+                valid_lft: None,
+                preferred_lft: None,
+            });
+        }
+    }
+
+    Ok(links.into_values().collect())
 }
 
-#[derive(Default, Debug)]
+/// Interface counters. The first sixteen follow the columns of /proc/net/dev;
+/// the last three are only reported by IFLA_STATS64.
+#[derive(Default, Debug, Clone, Serialize)]
 pub struct Stats {
     pub rx_bytes: u64,
     pub rx_packets: u64,
+    pub rx_errors: u64,
+    pub rx_dropped: u64,
+    pub rx_fifo: u64,
+    pub rx_frame: u64,
+    pub rx_compressed: u64,
+    pub rx_multicast: u64,
 
     pub tx_bytes: u64,
     pub tx_packets: u64,
+    pub tx_errors: u64,
+    pub tx_dropped: u64,
+    pub tx_fifo: u64,
+    pub tx_collisions: u64,
+    pub tx_carrier: u64,
+    pub tx_compressed: u64,
+
+    pub rx_missed: Option<u64>,
+    pub rx_nohandler: Option<u64>,
+    pub rx_otherhost_dropped: Option<u64>,
 }
 
 #[cfg(target_os = "linux")]
@@ -146,7 +226,7 @@ pub fn get_stats(ifname: &str) -> Result<Stats> {
 
         let name = parts[0].trim_end_matches(':');
         if name == ifname {
-            if parts.len() < 11 {
+            if parts.len() < 17 {
                 break;
             } // Safety
 
@@ -155,8 +235,21 @@ pub fn get_stats(ifname: &str) -> Result<Stats> {
             return Ok(Stats {
                 rx_bytes: p(1),
                 rx_packets: p(2),
+                rx_errors: p(3),
+                rx_dropped: p(4),
+                rx_fifo: p(5),
+                rx_frame: p(6),
+                rx_compressed: p(7),
+                rx_multicast: p(8),
                 tx_bytes: p(9),
                 tx_packets: p(10),
+                tx_errors: p(11),
+                tx_dropped: p(12),
+                tx_fifo: p(13),
+                tx_collisions: p(14),
+                tx_carrier: p(15),
+                tx_compressed: p(16),
+                ..Default::default()
             });
         }
     }
@@ -173,37 +266,22 @@ pub fn get_stats(_ifname: &str) -> Result<Stats> {
     Ok(Stats::default())
 }
 
-pub fn get_inet6_addr(ifname: &str) -> Result<Vec<(SmolStr, u32, SmolStr)>> {
-    let addrs = nix::ifaddrs::getifaddrs()?;
-    let mut ret = Vec::new();
-    for ifa in addrs {
-        if ifa.interface_name == ifname {
-            if let Some(address) = ifa.address {
-                if let Some(sockaddr) = address.as_sockaddr_in6() {
-                    let ip = sockaddr.ip();
-                    // Calculate prefix len from netmask if available
-                    let mut prefix = 0;
-                    if let Some(netmask) = ifa.netmask.as_ref().and_then(|a| a.as_sockaddr_in6()) {
-                        let mask_ip = netmask.ip();
-                        prefix = u128::from(mask_ip).count_ones();
-                    }
-                    // Simplified scope detection
-                    let scope = if ip.is_loopback() {
-                        "host"
-                    } else if ip.is_unicast_link_local() {
-                        "link"
-                    } else if ip.octets()[0] == 0xfe && (ip.octets()[1] & 0xc0) == 0xc0 {
-                        "site"
-                    } else if ip.is_multicast() {
-                        "multicast"
-                    } else {
-                        "global"
-                    };
-
-                    ret.push((SmolStr::from(ip.to_string()), prefix, SmolStr::from(scope)));
-                }
-            }
-        }
+#[derive(Debug, Clone, Serialize)]
+pub struct Inet6Addr {
+    pub addr: SmolStr,
+    pub prefix: u32,
+    pub scope: SmolStr,
+    pub flags: Vec<SmolStr>, // IFA_F_* names: tentative, dadfailed, deprecated...
+    pub valid_lft: Option<u32>, // seconds, None when forever (or unknown)
+    pub preferred_lft: Option<u32>,
+}
+
+impl Inet6Addr {
+    /// Duplicate address detection has not completed, or has failed: the
+    /// address cannot be used.
+    pub fn dad_pending_or_failed(&self) -> bool {
+        self.flags
+            .iter()
+            .any(|f| f == "tentative" || f == "dadfailed")
     }
-    Ok(ret)
 }
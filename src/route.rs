@@ -0,0 +1,80 @@
+//! Routes that leave through an interface, from RTM_GETROUTE.
+//!
+//! Only the routes that say something about the interface itself are kept:
+//! default routes, the prefixes the kernel adds for its addresses, and the
+//! routes learned from router advertisements or DHCP.
+
+use serde::Serialize;
+use smol_str::SmolStr;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Route {
+    /// `default` or `prefix/len`.
+    pub destination: SmolStr,
+    pub gateway: Option<SmolStr>,
+    pub metric: Option<u32>,
+    /// main, default or the table number.
+    pub table: SmolStr,
+    /// kernel, boot, static, ra, dhcp... (rtm_protocol).
+    pub protocol: SmolStr,
+    /// One next hop of a multipath route.
+    pub multipath: bool,
+}
+
+impl Route {
+    pub fn is_default(&self) -> bool {
+        self.destination == "default"
+    }
+
+    /// `via 10.0.0.1 metric 100 table 10 proto dhcp`, leaving out what is unset
+    /// and the main table.
+    pub fn details_str(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(gateway) = &self.gateway {
+            parts.push(format!("via {}", gateway));
+        }
+        if let Some(metric) = self.metric {
+            parts.push(format!("metric {}", metric));
+        }
+        if self.table != "main" {
+            parts.push(format!("table {}", self.table));
+        }
+        parts.push(format!("proto {}", self.protocol));
+        if self.multipath {
+            parts.push("multipath".to_string());
+        }
+        parts.join(" ")
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn table_name(table: u32) -> SmolStr {
+    match table {
+        253 => SmolStr::new_static("default"),
+        254 => SmolStr::new_static("main"),
+        255 => SmolStr::new_static("local"),
+        _ => smol_str::format_smolstr!("{}", table),
+    }
+}
+
+/// RTPROT_* names, as in /etc/iproute2/rt_protos.
+#[cfg(target_os = "linux")]
+pub fn protocol_name(protocol: u8) -> SmolStr {
+    match protocol {
+        1 => SmolStr::new_static("redirect"),
+        2 => SmolStr::new_static("kernel"),
+        3 => SmolStr::new_static("boot"),
+        4 => SmolStr::new_static("static"),
+        9 => SmolStr::new_static("ra"),
+        11 => SmolStr::new_static("zebra"),
+        12 => SmolStr::new_static("bird"),
+        16 => SmolStr::new_static("dhcp"),
+        18 => SmolStr::new_static("keepalived"),
+        42 => SmolStr::new_static("babel"),
+        186 => SmolStr::new_static("bgp"),
+        187 => SmolStr::new_static("isis"),
+        188 => SmolStr::new_static("ospf"),
+        189 => SmolStr::new_static("rip"),
+        _ => smol_str::format_smolstr!("{}", protocol),
+    }
+}
@@ -0,0 +1,80 @@
+//! Virtual functions of an SR-IOV physical function, from IFLA_VFINFO_LIST.
+
+use serde::Serialize;
+use smol_str::SmolStr;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VfInfo {
+    pub vf: u32,
+    pub mac: Option<SmolStr>,
+    pub vlan: Option<u32>,
+    pub qos: Option<u32>,
+    /// 802.1Q or 802.1ad, when the driver reports the VLAN list.
+    pub vlan_protocol: Option<SmolStr>,
+    /// None when the driver does not support the setting.
+    pub spoofchk: Option<bool>,
+    pub trust: Option<bool>,
+    /// auto, enable or disable.
+    pub link_state: Option<SmolStr>,
+    pub min_tx_rate: Option<u32>, // Mb/s
+    pub max_tx_rate: Option<u32>, // Mb/s
+    pub stats: Option<VfStats>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VfStats {
+    pub rx_packets: u64,
+    pub tx_packets: u64,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub broadcast: u64,
+    pub multicast: u64,
+    pub rx_dropped: u64,
+    pub tx_dropped: u64,
+}
+
+impl VfInfo {
+    /// `vlan 10 qos 2, spoofchk on, trust off, link auto, max_tx_rate 1000Mbps`,
+    /// as `ip link` lists a VF, leaving out what is unset.
+    pub fn details_str(&self) -> String {
+        let on_off = |b: bool| if b { "on" } else { "off" };
+        let mut parts = Vec::new();
+        if let Some(vlan) = self.vlan.filter(|&v| v != 0) {
+            let mut s = format!("vlan {}", vlan);
+            if let Some(qos) = self.qos.filter(|&q| q != 0) {
+                s.push_str(&format!(" qos {}", qos));
+            }
+            if let Some(proto) = self.vlan_protocol.as_ref().filter(|p| *p != "802.1Q") {
+                s.push_str(&format!(" proto {}", proto));
+            }
+            parts.push(s);
+        }
+        if let Some(spoofchk) = self.spoofchk {
+            parts.push(format!("spoofchk {}", on_off(spoofchk)));
+        }
+        if let Some(trust) = self.trust {
+            parts.push(format!("trust {}", on_off(trust)));
+        }
+        if let Some(state) = &self.link_state {
+            parts.push(format!("link {}", state));
+        }
+        if let Some(rate) = self.min_tx_rate.filter(|&r| r != 0) {
+            parts.push(format!("min_tx_rate {}Mbps", rate));
+        }
+        if let Some(rate) = self.max_tx_rate.filter(|&r| r != 0) {
+            parts.push(format!("max_tx_rate {}Mbps", rate));
+        }
+        parts.join(", ")
+    }
+}
+
+/// IFLA_VF_LINK_STATE_* names.
+#[cfg(target_os = "linux")]
+pub fn link_state_name(state: u32) -> Option<&'static str> {
+    match state {
+        0 => Some("auto"),
+        1 => Some("enable"),
+        2 => Some("disable"),
+        _ => None,
+    }
+}
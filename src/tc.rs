@@ -0,0 +1,97 @@
+//! Queueing disciplines of an interface, from RTM_GETQDISC.
+//!
+//! Only the qdiscs attached to the device itself are kept: the root one, the
+//! ingress or clsact one, and the per TX queue children of mq and mqprio.
+
+use serde::Serialize;
+use smol_str::SmolStr;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Qdisc {
+    pub kind: SmolStr,
+    /// `major:minor` in hex, as printed by tc.
+    pub handle: SmolStr,
+    /// `root`, `ingress`, `clsact`, or the parent class.
+    pub parent: SmolStr,
+    /// TX queue of a child of mq/mqprio.
+    pub tx_queue: Option<u32>,
+    /// Key options, e.g. `limit 10240p`, `target 5ms`.
+    pub params: Vec<SmolStr>,
+    pub bytes: u64,
+    pub packets: u64,
+    pub backlog: u32, // bytes
+    pub qlen: u32,    // packets
+    pub drops: u32,
+    pub overlimits: u32,
+    pub requeues: u32,
+    pub children: Vec<Qdisc>,
+}
+
+impl Qdisc {
+    /// `pfifo_fast 0: root bands 3`, `fq 0: parent 1:2 limit 10000p`,
+    /// `clsact ffff:`
+    pub fn summary(&self) -> String {
+        let mut parts = vec![format!("{} {}", self.kind, self.handle)];
+        match self.parent.as_str() {
+            // ingress and clsact name their own parent.
+            parent if parent == self.kind => {}
+            "root" | "ingress" => parts.push(self.parent.to_string()),
+            parent => parts.push(format!("parent {}", parent)),
+        }
+        parts.extend(self.params.iter().map(|p| p.to_string()));
+        parts.join(" ")
+    }
+
+    /// `Sent 1200 bytes 10 pkts, backlog 0b 0p, dropped 0, overlimits 0, requeues 0`
+    pub fn stats_str(&self) -> String {
+        format!(
+            "Sent {} bytes {} pkts, backlog {}b {}p, dropped {}, overlimits {}, requeues {}",
+            self.bytes,
+            self.packets,
+            self.backlog,
+            self.qlen,
+            self.drops,
+            self.overlimits,
+            self.requeues
+        )
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub const TC_H_ROOT: u32 = 0xffff_ffff;
+#[cfg(target_os = "linux")]
+pub const TC_H_INGRESS: u32 = 0xffff_fff1;
+
+/// `8001:1`, `ffff:` or `0:` for the major-only handles.
+#[cfg(target_os = "linux")]
+pub fn handle_str(handle: u32) -> SmolStr {
+    let (major, minor) = (handle >> 16, handle & 0xffff);
+    if minor == 0 {
+        smol_str::format_smolstr!("{:x}:", major)
+    } else {
+        smol_str::format_smolstr!("{:x}:{:x}", major, minor)
+    }
+}
+
+/// Bytes per second as tc prints it: `100Mbit`, `1500Kbit`.
+#[cfg(target_os = "linux")]
+pub fn rate_str(bytes_per_sec: u64) -> String {
+    let bits = bytes_per_sec * 8;
+    match bits {
+        b if b >= 1_000_000_000 && b.is_multiple_of(1_000_000_000) => {
+            format!("{}Gbit", b / 1_000_000_000)
+        }
+        b if b >= 1_000_000 => format!("{}Mbit", b / 1_000_000),
+        b if b >= 1_000 => format!("{}Kbit", b / 1_000),
+        b => format!("{}bit", b),
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn usecs_str(usecs: u64) -> String {
+    if usecs >= 1000 && usecs.is_multiple_of(1000) {
+        format!("{}ms", usecs / 1000)
+    } else {
+        format!("{}us", usecs)
+    }
+}